@@ -0,0 +1,322 @@
+mod roster;
+mod vault;
+
+use std::{
+    io::{Write, stdin, stdout},
+    path::PathBuf,
+    time::Duration,
+};
+
+use anyhow::Context;
+use clap::{Parser, Subcommand, ValueEnum};
+use eaccess::{Client, Config, F, G, GameCode, NProtocol, P, Retry};
+use tokio::io::{AsyncBufReadExt, BufReader};
+
+/// Poke at the play.net eaccess (SGE) login server.
+#[derive(Parser)]
+#[command(version)]
+struct Cli {
+    /// play.net account name; may be left out if the vault holds a single account
+    #[arg(short, long, env = "EACCESS_ACCOUNT", global = true)]
+    account: Option<String>,
+    /// play.net password; taken from the vault, or prompted for, when not set
+    #[arg(long, env = "EACCESS_PASSWORD", hide_env_values = true, global = true)]
+    password: Option<String>,
+    /// credential vault [default: $CONFIG_DIR/rogue/vault]
+    #[arg(long, env = "ROGUE_VAULT", global = true)]
+    vault: Option<PathBuf>,
+    /// vault passphrase; prompted for when not set
+    #[arg(
+        long,
+        env = "ROGUE_VAULT_PASSPHRASE",
+        hide_env_values = true,
+        global = true
+    )]
+    vault_passphrase: Option<String>,
+    /// eaccess server to connect to, as host:port
+    #[arg(long, default_value = "eaccess.play.net:7900", global = true)]
+    server: String,
+    /// seconds to wait for each response
+    #[arg(long, default_value_t = 15, global = true)]
+    step_timeout: u64,
+    /// seconds the whole login may take, retries included
+    #[arg(long, default_value_t = 60, global = true)]
+    login_deadline: u64,
+    /// how many times to try logging in before giving up
+    #[arg(long, default_value_t = 4, global = true)]
+    attempts: u32,
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// List the games (instances) the account can see.
+    Games,
+    /// List the characters on a game.
+    Characters {
+        /// game code, e.g. GS3 or DR [default: the account's default game]
+        #[arg(short, long)]
+        game: Option<GameCode>,
+    },
+    /// Show subscription info (F/G/P) for a game.
+    Status {
+        /// game code, e.g. GS3 or DR [default: the account's default game]
+        #[arg(short, long)]
+        game: Option<GameCode>,
+    },
+    /// Get a launch ticket for a character.
+    Launch {
+        /// game code, e.g. GS3 or DR [default: the account's default game]
+        #[arg(short, long)]
+        game: Option<GameCode>,
+        /// character name (case-insensitive) or id [default: the account's default character]
+        #[arg(short, long)]
+        character: Option<String>,
+        #[arg(short, long, value_enum, default_value_t = Format::Sal)]
+        format: Format,
+        /// the stream the game server should send
+        #[arg(short, long, value_enum, default_value_t = Protocol::Storm)]
+        protocol: Protocol,
+    },
+    /// Send raw requests read from stdin, one per line, and print the responses.
+    ///
+    /// `\t` in the input is sent as a tab.
+    Raw {
+        /// don't log in before reading requests
+        #[arg(long)]
+        no_login: bool,
+    },
+    /// List every character on every game, without launching anything.
+    Roster {
+        /// walk every account in the vault instead of just one
+        #[arg(long)]
+        all_accounts: bool,
+        #[arg(long)]
+        json: bool,
+    },
+    /// Manage the credential vault.
+    Vault {
+        #[command(subcommand)]
+        command: vault::Command,
+    },
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum Protocol {
+    /// XML, for StormFront-style frontends
+    Storm,
+    /// plain text, for the Wizard and other non-XML frontends
+    Play,
+}
+
+impl From<Protocol> for NProtocol<'_> {
+    fn from(value: Protocol) -> Self {
+        match value {
+            Protocol::Storm => Self::Storm,
+            Protocol::Play => Self::Play,
+        }
+    }
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum Format {
+    /// a .sal launch file
+    Sal,
+    Json,
+    /// shell `export` lines
+    Env,
+}
+
+#[tokio::main]
+async fn main() -> anyhow::Result<()> {
+    setup_tracing()?;
+
+    let cli = Cli::parse();
+    let vault_options = cli.vault_options();
+    let config = cli.config();
+
+    if let Command::Vault { command } = cli.command {
+        return vault::run(&vault_options, command);
+    }
+
+    if let Command::Roster { all_accounts, json } = cli.command {
+        let accounts = if all_accounts {
+            vault::accounts(&vault_options)?
+        } else {
+            vec![vault::credentials(
+                &vault_options,
+                cli.account,
+                cli.password,
+            )?]
+        };
+        return roster::run(&cli.server, &config, &accounts, json).await;
+    }
+
+    let account = if matches!(cli.command, Command::Raw { no_login: true }) {
+        None
+    } else {
+        Some(vault::credentials(
+            &vault_options,
+            cli.account,
+            cli.password,
+        )?)
+    };
+    let default_game = || {
+        account
+            .as_ref()
+            .and_then(|a| a.default_game.as_deref())
+            .map(GameCode::from)
+            .context("no --game given, and the account has no default game")
+    };
+
+    tracing::trace!("connecting to {}", cli.server);
+    let mut client = match &account {
+        Some(account) => {
+            Client::connect_and_login(
+                cli.server.as_str(),
+                &account.account,
+                &account.password,
+                config,
+            )
+            .await?
+        }
+        None => Client::connect_with(cli.server.as_str(), config).await?,
+    };
+
+    match cli.command {
+        Command::Games => {
+            for (code, name) in client.games().await?.0 {
+                println!("{code}\t{name}");
+            }
+        }
+        Command::Characters { game } => {
+            let game = game.map_or_else(default_game, Ok)?;
+            let res = client.characters(&game).await?;
+            println!("{}/{} characters", res.num_characters, res.max_characters);
+            for (id, name) in res.characters {
+                println!("{id}\t{name}");
+            }
+        }
+        Command::Status { game } => {
+            let game = game.map_or_else(default_game, Ok)?;
+            let res = client.request::<F>(F::out(&game)).await?;
+            println!("payment status: {}", <&str>::from(res.0));
+            let res = client.request::<G>(G::out(&game)).await?;
+            println!("name: {}", res.name);
+            println!("model: {}", <&str>::from(res.model));
+            for (key, value) in res.data {
+                println!("{key}: {value}");
+            }
+            let res = client.request::<P>(P::out(&game)).await?;
+            println!(
+                "p: {} {} {} {} {} {}",
+                res.p0, res.p1, res.p2, res.p3, res.p4, res.p5
+            );
+        }
+        Command::Launch {
+            game,
+            character,
+            format,
+            protocol,
+        } => {
+            let game = game.map_or_else(default_game, Ok)?;
+            let character = character
+                .or_else(|| account.as_ref()?.default_character.clone())
+                .context("no --character given, and the account has no default character")?;
+
+            let [ticket] = client
+                .launch_all(&[(game, &character)], protocol.into())
+                .await?
+                .try_into()
+                .unwrap();
+            let res = ticket.as_l();
+            match format {
+                Format::Sal => print!("{}", res.to_sal()),
+                Format::Json => println!(
+                    "{:#}",
+                    serde_json::json!({
+                        "upport": res.upport,
+                        "game": res.game,
+                        "game_code": res.game_code,
+                        "full_game_name": res.full_game_name,
+                        "game_file": res.game_file,
+                        "game_host": res.game_host,
+                        "game_port": res.game_port,
+                        "key": res.key,
+                    })
+                ),
+                Format::Env => {
+                    for (name, value) in [
+                        ("UPPORT", res.upport.to_string().as_str()),
+                        ("GAME", res.game),
+                        ("GAMECODE", res.game_code),
+                        ("FULLGAMENAME", res.full_game_name),
+                        ("GAMEFILE", res.game_file),
+                        ("GAMEHOST", res.game_host),
+                        ("GAMEPORT", res.game_port.to_string().as_str()),
+                        ("KEY", res.key),
+                    ] {
+                        println!("export EACCESS_{name}='{}'", value.replace('\'', r"'\''"));
+                    }
+                }
+            }
+        }
+        Command::Raw { .. } => {
+            let mut lines = BufReader::new(tokio::io::stdin()).lines();
+            while let Some(line) = lines.next_line().await? {
+                client
+                    .send(format!("{}\n", line.replace(r"\t", "\t")))
+                    .await?;
+                print!("{}", client.read_line().await?.replace('\t', r"\t"));
+            }
+        }
+        Command::Roster { .. } | Command::Vault { .. } => unreachable!(),
+    }
+
+    Ok(())
+}
+
+impl Cli {
+    fn config(&self) -> Config {
+        Config {
+            step_timeout: Duration::from_secs(self.step_timeout),
+            login_deadline: Some(Duration::from_secs(self.login_deadline)),
+            retry: Retry {
+                attempts: self.attempts,
+                ..Retry::default()
+            },
+            ..Config::default()
+        }
+    }
+
+    fn vault_options(&self) -> vault::Options {
+        vault::Options {
+            path: self.vault.clone().or_else(rogue_vault::Vault::default_path),
+            passphrase: self.vault_passphrase.clone(),
+        }
+    }
+}
+
+fn prompt(message: &str) -> anyhow::Result<String> {
+    print!("{message}");
+    stdout().flush()?;
+
+    let mut line = String::new();
+    stdin().read_line(&mut line)?;
+    Ok(line.trim_end_matches(['\r', '\n']).to_owned())
+}
+
+fn setup_tracing() -> anyhow::Result<()> {
+    use tracing::subscriber::set_global_default;
+    use tracing_subscriber::{EnvFilter, fmt::Subscriber};
+
+    set_global_default(
+        Subscriber::builder()
+            .with_env_filter(EnvFilter::from_default_env())
+            .with_writer(std::io::stderr)
+            .finish(),
+    )?;
+
+    Ok(())
+}
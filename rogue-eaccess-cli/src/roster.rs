@@ -0,0 +1,80 @@
+use eaccess::{Client, Config, Roster, retry};
+use rogue_vault::Account;
+
+/// Logs in to each account in turn and prints everything it holds.
+pub async fn run(
+    server: &str,
+    config: &Config,
+    accounts: &[Account],
+    json: bool,
+) -> anyhow::Result<()> {
+    let mut rosters = Vec::new();
+    for account in accounts {
+        tracing::trace!("collecting roster for {}", account.account);
+        // the walk only uses M, G and C, so a dropped connection can start over from scratch;
+        // each attempt logs in once, since this is the only retry loop
+        let roster = retry(&config.retry, || async {
+            let mut client = Client::connect_with(server, config.clone()).await?;
+            client.login(&account.account, &account.password).await?;
+            client.roster().await
+        })
+        .await?;
+        rosters.push((account.account.as_str(), roster));
+    }
+
+    if json {
+        println!("{:#}", to_json(&rosters));
+        return Ok(());
+    }
+
+    println!("account\tgame\tstatus\tslots\tid\tcharacter");
+    for (account, roster) in &rosters {
+        for failure in &roster.failed {
+            eprintln!(
+                "{account}: couldn't list {}: {}",
+                failure.code, failure.error
+            );
+        }
+        for game in &roster.games {
+            let slots = format!("{}/{}", game.num_characters, game.max_characters);
+            if game.characters.is_empty() {
+                println!("{account}\t{}\t{}\t{slots}\t-\t-", game.code, game.status);
+            }
+            for character in &game.characters {
+                println!(
+                    "{account}\t{}\t{}\t{slots}\t{}\t{}",
+                    game.code, game.status, character.id, character.name
+                );
+            }
+        }
+    }
+
+    Ok(())
+}
+
+fn to_json(rosters: &[(&str, Roster)]) -> serde_json::Value {
+    rosters
+        .iter()
+        .map(|(account, roster)| {
+            serde_json::json!({
+                "account": account,
+                "games": roster.games.iter().map(|game| serde_json::json!({
+                    "code": game.code,
+                    "name": game.name,
+                    "status": game.status,
+                    "num_characters": game.num_characters,
+                    "max_characters": game.max_characters,
+                    "characters": game.characters.iter().map(|character| serde_json::json!({
+                        "id": character.id,
+                        "name": character.name,
+                    })).collect::<Vec<_>>(),
+                })).collect::<Vec<_>>(),
+                "failed": roster.failed.iter().map(|failure| serde_json::json!({
+                    "code": failure.code,
+                    "name": failure.name,
+                    "error": failure.error.to_string(),
+                })).collect::<Vec<_>>(),
+            })
+        })
+        .collect()
+}
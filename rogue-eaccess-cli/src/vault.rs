@@ -0,0 +1,165 @@
+use std::path::PathBuf;
+
+use anyhow::Context;
+use clap::Subcommand;
+use rogue_vault::{Account, Vault, prompt_secret};
+
+use crate::prompt;
+
+pub struct Options {
+    pub path: Option<PathBuf>,
+    pub passphrase: Option<String>,
+}
+
+impl Options {
+    fn path(&self) -> anyhow::Result<&PathBuf> {
+        self.path
+            .as_ref()
+            .context("no --vault given, and there is no config directory to default to")
+    }
+
+    fn passphrase(&self) -> anyhow::Result<String> {
+        match &self.passphrase {
+            Some(passphrase) => Ok(passphrase.clone()),
+            None => Ok(prompt_secret("Vault passphrase: ")?),
+        }
+    }
+
+    fn open(&self) -> anyhow::Result<(Vault, String)> {
+        let path = self.path()?;
+        let passphrase = self.passphrase()?;
+        let vault = Vault::open(path, &passphrase)
+            .with_context(|| format!("failed to open {}", path.display()))?;
+        Ok((vault, passphrase))
+    }
+
+    fn open_or_create(&self) -> anyhow::Result<(Vault, String)> {
+        if self.path()?.exists() {
+            return self.open();
+        }
+
+        let passphrase = self.passphrase()?;
+        if self.passphrase.is_none() && prompt_secret("Repeat passphrase: ")? != passphrase {
+            anyhow::bail!("passphrases don't match");
+        }
+        Ok((Vault::default(), passphrase))
+    }
+}
+
+#[derive(Subcommand)]
+pub enum Command {
+    /// Add an account, or replace the one with the same name.
+    Add {
+        /// play.net account name
+        account: String,
+        /// game code to use when none is given
+        #[arg(short, long)]
+        game: Option<String>,
+        /// character to launch when none is given
+        #[arg(short, long)]
+        character: Option<String>,
+    },
+    /// List the accounts in the vault.
+    List,
+    /// Remove an account.
+    Remove { account: String },
+}
+
+pub fn run(options: &Options, command: Command) -> anyhow::Result<()> {
+    match command {
+        Command::Add {
+            account,
+            game,
+            character,
+        } => {
+            let (mut vault, passphrase) = options.open_or_create()?;
+            let password = prompt_secret(&format!("Password for {account}: "))?;
+            vault.insert(Account {
+                account,
+                password,
+                default_game: game,
+                default_character: character,
+            });
+            vault.save(options.path()?, &passphrase)?;
+        }
+        Command::List => {
+            let (vault, _) = options.open()?;
+            for account in &vault.accounts {
+                println!(
+                    "{}\t{}\t{}",
+                    account.account,
+                    account.default_game.as_deref().unwrap_or("-"),
+                    account.default_character.as_deref().unwrap_or("-"),
+                );
+            }
+        }
+        Command::Remove { account } => {
+            let (mut vault, passphrase) = options.open()?;
+            if !vault.remove(&account) {
+                anyhow::bail!("{account} is not in the vault");
+            }
+            vault.save(options.path()?, &passphrase)?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Every account in the vault.
+pub fn accounts(options: &Options) -> anyhow::Result<Vec<Account>> {
+    let (vault, _) = options.open()?;
+    Ok(vault.accounts.clone())
+}
+
+/// Works out which account to log in with.
+///
+/// Explicit `account` and `password` win. Otherwise the account comes from the vault if there is
+/// one, and as a last resort both are prompted for (the password without echo).
+pub fn credentials(
+    options: &Options,
+    account: Option<String>,
+    password: Option<String>,
+) -> anyhow::Result<Account> {
+    let vault_exists = options.path.as_ref().is_some_and(|path| path.exists());
+
+    let mut found = match (&account, &password) {
+        (Some(_), Some(_)) => None,
+        _ if vault_exists => {
+            let (vault, _) = options.open()?;
+            Some(match &account {
+                Some(name) => vault
+                    .account(name)
+                    .with_context(|| format!("{name} is not in the vault"))?
+                    .clone(),
+                None => match vault.accounts.as_slice() {
+                    [account] => account.clone(),
+                    [] => anyhow::bail!("the vault has no accounts"),
+                    _ => anyhow::bail!("the vault has several accounts; pick one with --account"),
+                },
+            })
+        }
+        _ => None,
+    };
+
+    if let Some(found) = &mut found {
+        if let Some(password) = password {
+            found.password = password;
+        }
+        return Ok(found.clone());
+    }
+
+    let account = match account {
+        Some(account) => account,
+        None => prompt("Account name: ")?,
+    };
+    let password = match password {
+        Some(password) => password,
+        None => prompt_secret("Password: ")?,
+    };
+    Ok(Account {
+        account,
+        password,
+        default_game: None,
+        default_character: None,
+    })
+}
@@ -0,0 +1,132 @@
+use std::{
+    io::{BufRead, BufReader, Write},
+    net::TcpListener,
+    process::Command,
+    sync::{
+        Arc,
+        atomic::{AtomicU32, Ordering},
+    },
+    thread,
+};
+
+/// Serves one connection, answering each request by its first letter, and returns the address
+/// to pass to `--server`.
+fn mock_eaccess() -> String {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let addr = listener.local_addr().unwrap();
+
+    thread::spawn(move || {
+        let (stream, _) = listener.accept().unwrap();
+        let mut writer = stream.try_clone().unwrap();
+        for line in BufReader::new(stream).split(b'\n') {
+            let response = match line.unwrap().as_slice() {
+                [b'K', ..] => "IKJPXQOEHDGHMPBIQLAJQSBTAMWFRMVS\n",
+                [b'A', ..] => "A\tACCOUNT\tKEY\t4e2a7ad3\tSome Person\n",
+                [b'M', ..] => "M\tGS3\tGemStone IV\tDR\tDragonRealms\n",
+                [b'G', ..] => "G\tGemStone IV\tFREE_TO_PLAY\t0\t\tROOT=sgc/gs\n",
+                [b'C', ..] => "C\t1\t5\t1\t1\tW_ACCOUNT_000\tFoo\n",
+                [b'L', ..] => {
+                    "L\tOK\tUPPORT=5535\tGAME=STORM\tGAMECODE=GS3\tFULLGAMENAME=Wrayth\tGAMEFILE=WRAYTH.EXE\tGAMEHOST=storm.gs4.game.play.net\tGAMEPORT=10024\tKEY=4e2a7ad3f1c90e6b\n"
+                }
+                _ => "X\n",
+            };
+            writer.write_all(response.as_bytes()).unwrap();
+        }
+    });
+
+    addr.to_string()
+}
+
+fn cli(server: &str) -> Command {
+    let mut command = Command::new(env!("CARGO_BIN_EXE_eaccess-cli"));
+    command
+        .args(["--server", server, "--account", "ACCOUNT"])
+        .env("EACCESS_PASSWORD", "hunter2")
+        .env("ROGUE_VAULT", "/nonexistent/rogue/vault")
+        .env_remove("EACCESS_ACCOUNT")
+        .env_remove("ROGUE_VAULT_PASSPHRASE");
+    command
+}
+
+/// Runs the CLI against a fresh mock, logged in as ACCOUNT, and returns its stdout.
+fn run(args: &[&str]) -> String {
+    let output = cli(&mock_eaccess()).args(args).output().unwrap();
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    String::from_utf8(output.stdout).unwrap()
+}
+
+#[test]
+fn games() {
+    assert_eq!(run(&["games"]), "GS3\tGemStone IV\nDR\tDragonRealms\n");
+}
+
+#[test]
+fn launch_as_sal() {
+    assert_eq!(
+        run(&["launch", "--game", "GS3", "--character", "foo"]),
+        "UPPORT=5535\nGAME=STORM\nGAMECODE=GS3\nFULLGAMENAME=Wrayth\nGAMEFILE=WRAYTH.EXE\n\
+         GAMEHOST=storm.gs4.game.play.net\nGAMEPORT=10024\nKEY=4e2a7ad3f1c90e6b\n"
+    );
+}
+
+#[test]
+fn launch_as_json() {
+    let json: serde_json::Value = serde_json::from_str(&run(&[
+        "launch",
+        "--game",
+        "GS3",
+        "--character",
+        "W_ACCOUNT_000",
+        "--format",
+        "json",
+    ]))
+    .unwrap();
+    assert_eq!(json["game_host"], "storm.gs4.game.play.net");
+    assert_eq!(json["game_port"], 10024);
+    assert_eq!(json["key"], "4e2a7ad3f1c90e6b");
+}
+
+#[test]
+fn launch_as_env() {
+    let env = run(&["launch", "-g", "GS3", "-c", "Foo", "--format", "env"]);
+    assert!(env.contains("export EACCESS_GAMEHOST='storm.gs4.game.play.net'\n"));
+    assert!(env.contains("export EACCESS_GAMEPORT='10024'\n"));
+    assert!(env.contains("export EACCESS_KEY='4e2a7ad3f1c90e6b'\n"));
+}
+
+#[test]
+fn roster() {
+    assert_eq!(
+        run(&["roster"]),
+        "account\tgame\tstatus\tslots\tid\tcharacter\n\
+         ACCOUNT\tGS3\tFREE_TO_PLAY\t1/5\tW_ACCOUNT_000\tFoo\n\
+         ACCOUNT\tDR\tFREE_TO_PLAY\t1/5\tW_ACCOUNT_000\tFoo\n"
+    );
+}
+
+#[test]
+fn roster_logs_in_once_per_attempt() {
+    // hangs up on every connection
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let addr = listener.local_addr().unwrap().to_string();
+    let connections = Arc::new(AtomicU32::new(0));
+    let counter = connections.clone();
+    thread::spawn(move || {
+        for stream in listener.incoming() {
+            // counted before the hang-up, so it's in before the CLI can give up
+            counter.fetch_add(1, Ordering::Relaxed);
+            drop(stream.unwrap());
+        }
+    });
+
+    let output = cli(&addr)
+        .args(["--attempts", "2", "roster"])
+        .output()
+        .unwrap();
+    assert!(!output.status.success());
+    assert_eq!(connections.load(Ordering::Relaxed), 2);
+}
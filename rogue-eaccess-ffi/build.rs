@@ -0,0 +1,15 @@
+use std::{env, path::Path};
+
+fn main() {
+    let dir = env::var("CARGO_MANIFEST_DIR").unwrap();
+    println!("cargo::rerun-if-changed=src");
+    println!("cargo::rerun-if-changed=cbindgen.toml");
+    println!("cargo::rerun-if-env-changed=ROGUE_EACCESS_FFI_HEADER");
+
+    let bindings = cbindgen::generate(&dir).expect("failed to generate the C header");
+    // the checked-in header is only rewritten when asked for; the tests compare it against this
+    bindings.write_to_file(Path::new(&env::var("OUT_DIR").unwrap()).join("rogue_eaccess.h"));
+    if env::var_os("ROGUE_EACCESS_FFI_HEADER").is_some() {
+        bindings.write_to_file(Path::new(&dir).join("include/rogue_eaccess.h"));
+    }
+}
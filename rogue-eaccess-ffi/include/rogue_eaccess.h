@@ -0,0 +1,176 @@
+#ifndef ROGUE_EACCESS_H
+#define ROGUE_EACCESS_H
+
+/* Generated by cbindgen from rogue-eaccess-ffi; do not edit. */
+
+#include <stdarg.h>
+#include <stdbool.h>
+#include <stdint.h>
+#include <stdlib.h>
+
+// What a call came to; anything but `Ok` means the `out` pointer was left alone.
+typedef enum EaccessStatus {
+  EACCESS_STATUS_OK = 0,
+  // eaccess sent something that didn't parse, which includes a refused login
+  EACCESS_STATUS_PARSE = 1,
+  EACCESS_STATUS_IO = 2,
+  // eaccess closed the connection
+  EACCESS_STATUS_CLOSED = 3,
+  // a response didn't arrive in time
+  EACCESS_STATUS_TIMEOUT = 4,
+  // the login, retries included, didn't finish in time
+  EACCESS_STATUS_DEADLINE = 5,
+  // a required pointer was null, or a string wasn't valid UTF-8
+  EACCESS_STATUS_INVALID_ARGUMENT = 6,
+  // a bug in this library; the handle involved should not be used again
+  EACCESS_STATUS_PANIC = 7,
+  // no such character on the game
+  EACCESS_STATUS_UNKNOWN_CHARACTER = 8,
+} EaccessStatus;
+
+// A list of `(code, name)` pairs: game codes and names, or character ids and names.
+typedef struct EaccessList EaccessList;
+
+// A logged in connection to eaccess.
+typedef struct EaccessSession EaccessSession;
+
+// A launch ticket: where the game server is, and the key to give it.
+typedef struct EaccessTicket EaccessTicket;
+
+#ifdef __cplusplus
+extern "C" {
+#endif // __cplusplus
+
+// The message for the last failed call on this thread, or null if there wasn't one.
+//
+// The string stays valid until the next failing call on the same thread.
+const char *eaccess_last_error(void);
+
+// Connects and logs in.
+//
+// `server` is `host:port`, or null for play.net's server.
+//
+// # Safety
+//
+// The strings are null or valid and NUL-terminated, and `out` is valid for writes.
+enum EaccessStatus eaccess_login(const char *server,
+                                 const char *account,
+                                 const char *password,
+                                 struct EaccessSession **out);
+
+// Closes the connection. Null is ignored.
+//
+// # Safety
+//
+// `session` is null or came from [`eaccess_login`], and hasn't been freed already.
+void eaccess_session_free(struct EaccessSession *session);
+
+// Lists the games on the account.
+//
+// # Safety
+//
+// `session` came from [`eaccess_login`], and `out` is valid for writes.
+enum EaccessStatus eaccess_games(struct EaccessSession *session, struct EaccessList **out);
+
+// Selects `game` and lists its characters.
+//
+// # Safety
+//
+// `session` came from [`eaccess_login`], `game` is valid and NUL-terminated, and `out` is valid
+// for writes.
+enum EaccessStatus eaccess_characters(struct EaccessSession *session,
+                                      const char *game,
+                                      struct EaccessList **out);
+
+// The number of items, or 0 if `list` is null.
+//
+// # Safety
+//
+// `list` is null or came from [`eaccess_games`] or [`eaccess_characters`].
+uintptr_t eaccess_list_len(const struct EaccessList *list);
+
+// The game code or character id at `index`, or null if `list` is null or `index` is out of
+// bounds.
+//
+// # Safety
+//
+// `list` is null or came from [`eaccess_games`] or [`eaccess_characters`].
+const char *eaccess_list_code(const struct EaccessList *list, uintptr_t index);
+
+// The game or character name at `index`, or null if `list` is null or `index` is out of
+// bounds.
+//
+// # Safety
+//
+// `list` is null or came from [`eaccess_games`] or [`eaccess_characters`].
+const char *eaccess_list_name(const struct EaccessList *list, uintptr_t index);
+
+// Null is ignored.
+//
+// # Safety
+//
+// `list` is null or came from [`eaccess_games`] or [`eaccess_characters`], and hasn't been
+// freed already.
+void eaccess_list_free(struct EaccessList *list);
+
+// Gets a launch ticket for `character` (an id from [`eaccess_characters`]) on the game last
+// selected.
+//
+// `protocol` is null for `STORM`. This is never retried; see `Client::launch`.
+//
+// # Safety
+//
+// `session` came from [`eaccess_login`], the strings are valid and NUL-terminated (`protocol`
+// may be null), and `out` is valid for writes.
+enum EaccessStatus eaccess_launch(struct EaccessSession *session,
+                                  const char *character,
+                                  const char *protocol,
+                                  struct EaccessTicket **out);
+
+// The game code, or null if `ticket` is null.
+//
+// # Safety
+//
+// `ticket` is null or came from [`eaccess_launch`].
+const char *eaccess_ticket_game_code(const struct EaccessTicket *ticket);
+
+// The game server's host, or null if `ticket` is null.
+//
+// # Safety
+//
+// `ticket` is null or came from [`eaccess_launch`].
+const char *eaccess_ticket_game_host(const struct EaccessTicket *ticket);
+
+// The game server's port, or 0 if `ticket` is null.
+//
+// # Safety
+//
+// `ticket` is null or came from [`eaccess_launch`].
+uint64_t eaccess_ticket_game_port(const struct EaccessTicket *ticket);
+
+// The launch key, or null if `ticket` is null.
+//
+// # Safety
+//
+// `ticket` is null or came from [`eaccess_launch`].
+const char *eaccess_ticket_key(const struct EaccessTicket *ticket);
+
+// The whole ticket in the `.sal` launch file format, or null if `ticket` is null.
+//
+// # Safety
+//
+// `ticket` is null or came from [`eaccess_launch`].
+const char *eaccess_ticket_sal(const struct EaccessTicket *ticket);
+
+// Null is ignored.
+//
+// # Safety
+//
+// `ticket` is null or came from [`eaccess_launch`], and hasn't been freed already.
+void eaccess_ticket_free(struct EaccessTicket *ticket);
+
+#ifdef __cplusplus
+}  // extern "C"
+#endif  // __cplusplus
+
+#endif  /* ROGUE_EACCESS_H */
@@ -0,0 +1,416 @@
+//! A C API for the eaccess client. The header is `include/rogue_eaccess.h`; after changing the
+//! API, rebuild with `ROGUE_EACCESS_FFI_HEADER=1` set to regenerate it.
+//!
+//! Every call blocks. Functions that can fail return an [`Status`]; on failure,
+//! [`eaccess_last_error`] has the message. Everything handed out through an `out` pointer is
+//! owned by the caller and must be given back to its `_free` function. Strings borrowed from a
+//! handle live as long as the handle does.
+
+use std::{
+    cell::RefCell,
+    ffi::{CStr, CString, c_char},
+    panic::{AssertUnwindSafe, catch_unwind},
+    ptr,
+};
+
+use eaccess::{Client, Config, ENDPOINT, Error, NProtocol};
+use tokio::runtime::{Builder, Runtime};
+
+/// What a call came to; anything but `Ok` means the `out` pointer was left alone.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Status {
+    Ok = 0,
+    /// eaccess sent something that didn't parse, which includes a refused login
+    Parse = 1,
+    Io = 2,
+    /// eaccess closed the connection
+    Closed = 3,
+    /// a response didn't arrive in time
+    Timeout = 4,
+    /// the login, retries included, didn't finish in time
+    Deadline = 5,
+    /// a required pointer was null, or a string wasn't valid UTF-8
+    InvalidArgument = 6,
+    /// a bug in this library; the handle involved should not be used again
+    Panic = 7,
+    /// no such character on the game
+    UnknownCharacter = 8,
+}
+
+impl From<&Error> for Status {
+    fn from(value: &Error) -> Self {
+        match value {
+            Error::ParseError(_) => Self::Parse,
+            Error::Io { .. } => Self::Io,
+            Error::Closed(_) => Self::Closed,
+            Error::Timeout(_) => Self::Timeout,
+            Error::Deadline => Self::Deadline,
+            Error::UnknownCharacter { .. } => Self::UnknownCharacter,
+            Error::PartialLaunch { source, .. } => (&**source).into(),
+        }
+    }
+}
+
+thread_local! {
+    static LAST_ERROR: RefCell<Option<CString>> = const { RefCell::new(None) };
+}
+
+fn fail(status: Status, message: impl ToString) -> Status {
+    let message = CString::new(message.to_string().replace('\0', "")).unwrap();
+    LAST_ERROR.with(|e| *e.borrow_mut() = Some(message));
+    status
+}
+
+impl From<Error> for Status {
+    fn from(value: Error) -> Self {
+        fail((&value).into(), value)
+    }
+}
+
+/// Runs `f`, turning panics into [`Status::Panic`].
+fn guard(f: impl FnOnce() -> Result<(), Status>) -> Status {
+    match catch_unwind(AssertUnwindSafe(f)) {
+        Ok(Ok(())) => Status::Ok,
+        Ok(Err(status)) => status,
+        Err(_) => fail(Status::Panic, "rogue-eaccess panicked"),
+    }
+}
+
+/// # Safety
+///
+/// `s` is null or a valid, NUL-terminated string.
+unsafe fn str_arg<'a>(s: *const c_char, name: &str) -> Result<&'a str, Status> {
+    if s.is_null() {
+        return Err(fail(Status::InvalidArgument, format!("{name} is null")));
+    }
+    unsafe { CStr::from_ptr(s) }
+        .to_str()
+        .map_err(|_| fail(Status::InvalidArgument, format!("{name} isn't UTF-8")))
+}
+
+fn c_string(s: &str) -> CString {
+    // eaccess fields are tab and newline separated, and can't hold a NUL anyway
+    CString::new(s.replace('\0', "")).unwrap()
+}
+
+/// Hands `value` out through `out`.
+///
+/// # Safety
+///
+/// `out` is valid for writes.
+unsafe fn give<T>(out: *mut *mut T, value: T) {
+    unsafe { out.write(Box::into_raw(Box::new(value))) };
+}
+
+/// The message for the last failed call on this thread, or null if there wasn't one.
+///
+/// The string stays valid until the next failing call on the same thread.
+#[unsafe(no_mangle)]
+pub extern "C" fn eaccess_last_error() -> *const c_char {
+    LAST_ERROR.with(|e| e.borrow().as_ref().map_or(ptr::null(), |e| e.as_ptr()))
+}
+
+/// A logged in connection to eaccess.
+pub struct Session {
+    runtime: Runtime,
+    client: Client,
+}
+
+/// Connects and logs in.
+///
+/// `server` is `host:port`, or null for play.net's server.
+///
+/// # Safety
+///
+/// The strings are null or valid and NUL-terminated, and `out` is valid for writes.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn eaccess_login(
+    server: *const c_char,
+    account: *const c_char,
+    password: *const c_char,
+    out: *mut *mut Session,
+) -> Status {
+    guard(|| {
+        let server = (!server.is_null())
+            .then(|| unsafe { str_arg(server, "server") })
+            .transpose()?;
+        let account = unsafe { str_arg(account, "account") }?;
+        let password = unsafe { str_arg(password, "password") }?;
+        if out.is_null() {
+            return Err(fail(Status::InvalidArgument, "out is null"));
+        }
+
+        let runtime = Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .map_err(|e| fail(Status::Io, e))?;
+        let config = Config::default();
+        let client = runtime.block_on(async {
+            match server {
+                Some(server) => Client::connect_and_login(server, account, password, config).await,
+                None => Client::connect_and_login(ENDPOINT, account, password, config).await,
+            }
+        })?;
+
+        unsafe { give(out, Session { runtime, client }) };
+        Ok(())
+    })
+}
+
+/// Closes the connection. Null is ignored.
+///
+/// # Safety
+///
+/// `session` is null or came from [`eaccess_login`], and hasn't been freed already.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn eaccess_session_free(session: *mut Session) {
+    if !session.is_null() {
+        drop(unsafe { Box::from_raw(session) });
+    }
+}
+
+/// A list of `(code, name)` pairs: game codes and names, or character ids and names.
+pub struct List {
+    items: Vec<(CString, CString)>,
+}
+
+impl List {
+    fn new(items: &[(&str, &str)]) -> Self {
+        Self {
+            items: items
+                .iter()
+                .map(|(code, name)| (c_string(code), c_string(name)))
+                .collect(),
+        }
+    }
+}
+
+/// # Safety
+///
+/// `session` came from [`eaccess_login`], and `out` is valid for writes.
+unsafe fn session_call<T>(
+    session: *mut Session,
+    out: *mut *mut T,
+    f: impl AsyncFnOnce(&mut Client) -> Result<T, Error>,
+) -> Status {
+    guard(|| {
+        let Some(Session { runtime, client }) = (unsafe { session.as_mut() }) else {
+            return Err(fail(Status::InvalidArgument, "session is null"));
+        };
+        if out.is_null() {
+            return Err(fail(Status::InvalidArgument, "out is null"));
+        }
+
+        let value = runtime.block_on(f(client))?;
+        unsafe { give(out, value) };
+        Ok(())
+    })
+}
+
+/// Lists the games on the account.
+///
+/// # Safety
+///
+/// `session` came from [`eaccess_login`], and `out` is valid for writes.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn eaccess_games(session: *mut Session, out: *mut *mut List) -> Status {
+    unsafe {
+        session_call(session, out, async |client| {
+            client.games().await.map(|res| List::new(&res.0))
+        })
+    }
+}
+
+/// Selects `game` and lists its characters.
+///
+/// # Safety
+///
+/// `session` came from [`eaccess_login`], `game` is valid and NUL-terminated, and `out` is valid
+/// for writes.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn eaccess_characters(
+    session: *mut Session,
+    game: *const c_char,
+    out: *mut *mut List,
+) -> Status {
+    let game = match unsafe { str_arg(game, "game") } {
+        Ok(game) => game,
+        Err(status) => return status,
+    };
+    unsafe {
+        session_call(session, out, async |client| {
+            client
+                .characters(&game.into())
+                .await
+                .map(|res| List::new(&res.characters))
+        })
+    }
+}
+
+/// The item at `index`, or `None` if `list` is null or it's out of bounds.
+///
+/// # Safety
+///
+/// `list` is null or came from [`eaccess_games`] or [`eaccess_characters`].
+unsafe fn list_item<'a>(list: *const List, index: usize) -> Option<&'a (CString, CString)> {
+    unsafe { list.as_ref() }?.items.get(index)
+}
+
+/// The number of items, or 0 if `list` is null.
+///
+/// # Safety
+///
+/// `list` is null or came from [`eaccess_games`] or [`eaccess_characters`].
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn eaccess_list_len(list: *const List) -> usize {
+    unsafe { list.as_ref() }.map_or(0, |list| list.items.len())
+}
+
+/// The game code or character id at `index`, or null if `list` is null or `index` is out of
+/// bounds.
+///
+/// # Safety
+///
+/// `list` is null or came from [`eaccess_games`] or [`eaccess_characters`].
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn eaccess_list_code(list: *const List, index: usize) -> *const c_char {
+    unsafe { list_item(list, index) }.map_or(ptr::null(), |(code, _)| code.as_ptr())
+}
+
+/// The game or character name at `index`, or null if `list` is null or `index` is out of
+/// bounds.
+///
+/// # Safety
+///
+/// `list` is null or came from [`eaccess_games`] or [`eaccess_characters`].
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn eaccess_list_name(list: *const List, index: usize) -> *const c_char {
+    unsafe { list_item(list, index) }.map_or(ptr::null(), |(_, name)| name.as_ptr())
+}
+
+/// Null is ignored.
+///
+/// # Safety
+///
+/// `list` is null or came from [`eaccess_games`] or [`eaccess_characters`], and hasn't been
+/// freed already.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn eaccess_list_free(list: *mut List) {
+    if !list.is_null() {
+        drop(unsafe { Box::from_raw(list) });
+    }
+}
+
+/// A launch ticket: where the game server is, and the key to give it.
+pub struct Ticket {
+    game_code: CString,
+    game_host: CString,
+    game_port: u64,
+    key: CString,
+    sal: CString,
+}
+
+/// Gets a launch ticket for `character` (an id from [`eaccess_characters`]) on the game last
+/// selected.
+///
+/// `protocol` is null for `STORM`. This is never retried; see `Client::launch`.
+///
+/// # Safety
+///
+/// `session` came from [`eaccess_login`], the strings are valid and NUL-terminated (`protocol`
+/// may be null), and `out` is valid for writes.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn eaccess_launch(
+    session: *mut Session,
+    character: *const c_char,
+    protocol: *const c_char,
+    out: *mut *mut Ticket,
+) -> Status {
+    let args = (|| {
+        let character = unsafe { str_arg(character, "character") }?;
+        let protocol = if protocol.is_null() {
+            NProtocol::Storm
+        } else {
+            unsafe { str_arg(protocol, "protocol") }?.into()
+        };
+        Ok((character, protocol))
+    })();
+    let (character, protocol) = match args {
+        Ok(args) => args,
+        Err(status) => return status,
+    };
+    unsafe {
+        session_call(session, out, async |client| {
+            client.launch(character, protocol).await.map(|res| Ticket {
+                game_code: c_string(res.game_code),
+                game_host: c_string(res.game_host),
+                game_port: res.game_port,
+                key: c_string(res.key),
+                sal: c_string(&res.to_sal()),
+            })
+        })
+    }
+}
+
+/// The game code, or null if `ticket` is null.
+///
+/// # Safety
+///
+/// `ticket` is null or came from [`eaccess_launch`].
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn eaccess_ticket_game_code(ticket: *const Ticket) -> *const c_char {
+    unsafe { ticket.as_ref() }.map_or(ptr::null(), |ticket| ticket.game_code.as_ptr())
+}
+
+/// The game server's host, or null if `ticket` is null.
+///
+/// # Safety
+///
+/// `ticket` is null or came from [`eaccess_launch`].
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn eaccess_ticket_game_host(ticket: *const Ticket) -> *const c_char {
+    unsafe { ticket.as_ref() }.map_or(ptr::null(), |ticket| ticket.game_host.as_ptr())
+}
+
+/// The game server's port, or 0 if `ticket` is null.
+///
+/// # Safety
+///
+/// `ticket` is null or came from [`eaccess_launch`].
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn eaccess_ticket_game_port(ticket: *const Ticket) -> u64 {
+    unsafe { ticket.as_ref() }.map_or(0, |ticket| ticket.game_port)
+}
+
+/// The launch key, or null if `ticket` is null.
+///
+/// # Safety
+///
+/// `ticket` is null or came from [`eaccess_launch`].
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn eaccess_ticket_key(ticket: *const Ticket) -> *const c_char {
+    unsafe { ticket.as_ref() }.map_or(ptr::null(), |ticket| ticket.key.as_ptr())
+}
+
+/// The whole ticket in the `.sal` launch file format, or null if `ticket` is null.
+///
+/// # Safety
+///
+/// `ticket` is null or came from [`eaccess_launch`].
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn eaccess_ticket_sal(ticket: *const Ticket) -> *const c_char {
+    unsafe { ticket.as_ref() }.map_or(ptr::null(), |ticket| ticket.sal.as_ptr())
+}
+
+/// Null is ignored.
+///
+/// # Safety
+///
+/// `ticket` is null or came from [`eaccess_launch`], and hasn't been freed already.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn eaccess_ticket_free(ticket: *mut Ticket) {
+    if !ticket.is_null() {
+        drop(unsafe { Box::from_raw(ticket) });
+    }
+}
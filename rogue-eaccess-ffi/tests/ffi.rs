@@ -0,0 +1,136 @@
+use std::{
+    ffi::{CStr, CString, c_char},
+    io::{BufRead, BufReader, Write},
+    net::TcpListener,
+    ptr, thread,
+};
+
+use rogue_eaccess_ffi::*;
+
+/// Serves one connection, answering each request by its first letter. Only GS3 exists.
+fn mock_eaccess() -> CString {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let addr = listener.local_addr().unwrap();
+
+    thread::spawn(move || {
+        let (stream, _) = listener.accept().unwrap();
+        let mut writer = stream.try_clone().unwrap();
+        for line in BufReader::new(stream).split(b'\n') {
+            let response = match line.unwrap().as_slice() {
+                [b'K', ..] => "IKJPXQOEHDGHMPBIQLAJQSBTAMWFRMVS\n",
+                [b'A', ..] => "A\tACCOUNT\tKEY\t4e2a7ad3\tSome Person\n",
+                [b'M', ..] => "M\tGS3\tGemStone IV\tDR\tDragonRealms\n",
+                b"G\tGS3" => "G\tGemStone IV\tFREE_TO_PLAY\t0\t\tROOT=sgc/gs\n",
+                [b'C', ..] => "C\t1\t5\t1\t1\tW_ACCOUNT_000\tFoo\n",
+                [b'L', ..] => {
+                    "L\tOK\tUPPORT=5535\tGAME=STORM\tGAMECODE=GS3\tFULLGAMENAME=Wrayth\tGAMEFILE=WRAYTH.EXE\tGAMEHOST=storm.gs4.game.play.net\tGAMEPORT=10024\tKEY=4e2a7ad3f1c90e6b\n"
+                }
+                _ => "X\n",
+            };
+            writer.write_all(response.as_bytes()).unwrap();
+        }
+    });
+
+    CString::new(addr.to_string()).unwrap()
+}
+
+fn str<'a>(s: *const c_char) -> &'a str {
+    assert!(!s.is_null());
+    unsafe { CStr::from_ptr(s) }.to_str().unwrap()
+}
+
+#[test]
+fn login_list_and_launch() {
+    let server = mock_eaccess();
+    unsafe {
+        let mut session = ptr::null_mut();
+        let status = eaccess_login(
+            server.as_ptr(),
+            c"ACCOUNT".as_ptr(),
+            c"hunter2".as_ptr(),
+            &mut session,
+        );
+        assert_eq!(status, Status::Ok);
+
+        let mut games = ptr::null_mut();
+        assert_eq!(eaccess_games(session, &mut games), Status::Ok);
+        assert_eq!(eaccess_list_len(games), 2);
+        assert_eq!(str(eaccess_list_code(games, 1)), "DR");
+        assert_eq!(str(eaccess_list_name(games, 1)), "DragonRealms");
+        assert!(eaccess_list_code(games, 2).is_null());
+        eaccess_list_free(games);
+
+        let mut characters = ptr::null_mut();
+        assert_eq!(
+            eaccess_characters(session, c"GS3".as_ptr(), &mut characters),
+            Status::Ok
+        );
+        assert_eq!(str(eaccess_list_code(characters, 0)), "W_ACCOUNT_000");
+        eaccess_list_free(characters);
+
+        let mut ticket = ptr::null_mut();
+        assert_eq!(
+            eaccess_launch(session, c"W_ACCOUNT_000".as_ptr(), ptr::null(), &mut ticket),
+            Status::Ok
+        );
+        assert_eq!(
+            str(eaccess_ticket_game_host(ticket)),
+            "storm.gs4.game.play.net"
+        );
+        assert_eq!(eaccess_ticket_game_port(ticket), 10024);
+        assert_eq!(str(eaccess_ticket_key(ticket)), "4e2a7ad3f1c90e6b");
+        assert!(str(eaccess_ticket_sal(ticket)).contains("GAMEPORT=10024\n"));
+        eaccess_ticket_free(ticket);
+
+        let mut list = ptr::null_mut();
+        assert_eq!(
+            eaccess_characters(session, c"DR".as_ptr(), &mut list),
+            Status::Parse
+        );
+        assert!(list.is_null());
+        assert!(!eaccess_last_error().is_null());
+
+        eaccess_session_free(session);
+    }
+}
+
+#[test]
+fn null_arguments_are_rejected() {
+    unsafe {
+        let mut session = ptr::null_mut();
+        let status = eaccess_login(ptr::null(), ptr::null(), c"hunter2".as_ptr(), &mut session);
+        assert_eq!(status, Status::InvalidArgument);
+        assert_eq!(str(eaccess_last_error()), "account is null");
+        assert!(session.is_null());
+
+        let mut games = ptr::null_mut();
+        assert_eq!(
+            eaccess_games(ptr::null_mut(), &mut games),
+            Status::InvalidArgument
+        );
+    }
+}
+
+#[test]
+fn null_handles_read_as_empty() {
+    unsafe {
+        assert_eq!(eaccess_list_len(ptr::null()), 0);
+        assert!(eaccess_list_code(ptr::null(), 0).is_null());
+        assert!(eaccess_list_name(ptr::null(), 0).is_null());
+
+        assert!(eaccess_ticket_game_code(ptr::null()).is_null());
+        assert!(eaccess_ticket_game_host(ptr::null()).is_null());
+        assert_eq!(eaccess_ticket_game_port(ptr::null()), 0);
+        assert!(eaccess_ticket_key(ptr::null()).is_null());
+        assert!(eaccess_ticket_sal(ptr::null()).is_null());
+    }
+}
+
+#[test]
+fn header_is_up_to_date() {
+    assert!(
+        include_str!("../include/rogue_eaccess.h")
+            == include_str!(concat!(env!("OUT_DIR"), "/rogue_eaccess.h")),
+        "include/rogue_eaccess.h is stale; rebuild with ROGUE_EACCESS_FFI_HEADER=1 set"
+    );
+}
@@ -0,0 +1,197 @@
+//! A proxy that sits between an SGE frontend and eaccess, parsing everything that goes past and
+//! letting [`Hook`]s rewrite the responses.
+//!
+//! The conversation is strictly one response line per request line, so the proxy just takes
+//! turns: read a request, forward it, read the response, rewrite it, send it back.
+
+use std::{fmt, io, sync::Arc};
+
+use eaccess::{A, C, Client, Config, Error, F, G, K, L, M, Message, N, P, Step};
+use tokio::{
+    io::{AsyncBufReadExt, AsyncWriteExt, BufReader},
+    net::{TcpListener, TcpStream},
+};
+use tracing::Instrument;
+
+/// Rewrites responses on their way to the frontend.
+pub trait Hook: Send + Sync {
+    /// Called with every launch ticket that parses.
+    fn launch<'a>(&'a self, ticket: L<'a>) -> L<'a> {
+        ticket
+    }
+
+    /// Called with every response line, newline included, after any [`Hook::launch`] rewrite.
+    fn response(&self, step: Step, line: String) -> String {
+        let _ = step;
+        line
+    }
+}
+
+/// Points launch tickets at another game server, so the frontend connects through it (usually a
+/// local game proxy) instead of going straight to the game.
+#[derive(Debug, Clone)]
+pub struct Redirect {
+    pub game_host: String,
+    pub game_port: u64,
+}
+
+impl Hook for Redirect {
+    fn launch<'a>(&'a self, ticket: L<'a>) -> L<'a> {
+        tracing::info!(
+            from.host = ticket.game_host,
+            from.port = ticket.game_port,
+            to.host = self.game_host.as_str(),
+            to.port = self.game_port,
+            "redirecting launch"
+        );
+        L {
+            game_host: &self.game_host,
+            game_port: self.game_port,
+            ..ticket
+        }
+    }
+}
+
+pub struct Proxy {
+    upstream: String,
+    config: Config,
+    hooks: Vec<Box<dyn Hook>>,
+}
+
+impl Proxy {
+    /// A proxy to `upstream`, as host:port.
+    pub fn new(upstream: impl Into<String>) -> Self {
+        Self {
+            upstream: upstream.into(),
+            config: Config::default(),
+            hooks: Vec::new(),
+        }
+    }
+
+    /// Timeouts for the upstream connection. Nothing is ever retried, since the frontend is
+    /// the one driving the conversation.
+    pub fn with_config(mut self, config: Config) -> Self {
+        self.config = config;
+        self
+    }
+
+    /// Adds a hook. Hooks run in the order they were added.
+    pub fn hook(mut self, hook: impl Hook + 'static) -> Self {
+        self.hooks.push(Box::new(hook));
+        self
+    }
+
+    /// Accepts frontends forever, each on a task and upstream connection of its own.
+    pub async fn serve(self, listener: TcpListener) -> io::Result<()> {
+        let proxy = Arc::new(self);
+        loop {
+            let (frontend, peer) = listener.accept().await?;
+            let proxy = proxy.clone();
+            tokio::spawn(
+                async move {
+                    match proxy.handle(frontend).await {
+                        Ok(()) => tracing::debug!("frontend disconnected"),
+                        Err(e) => tracing::warn!(error = %e, "connection failed"),
+                    }
+                }
+                .instrument(tracing::info_span!("frontend", %peer)),
+            );
+        }
+    }
+
+    /// Proxies one frontend connection until either side hangs up.
+    pub async fn handle(&self, frontend: TcpStream) -> Result<(), Error> {
+        let mut upstream =
+            Client::connect_with(self.upstream.as_str(), self.config.clone()).await?;
+        let (frontend, mut writer) = frontend.into_split();
+        let mut frontend = BufReader::new(frontend);
+        let mut request = Vec::new();
+
+        loop {
+            request.clear();
+            let read = frontend
+                .read_until(b'\n', &mut request)
+                .await
+                .map_err(|source| Error::Io {
+                    step: Step::Other,
+                    source,
+                })?;
+            if read == 0 {
+                return Ok(());
+            }
+            let step = Step::of(&request);
+            // the request itself may hold a hashed password, so only the step is logged
+            tracing::debug!(%step, "request");
+
+            upstream.send(&request).await?;
+            let response = self.rewrite(step, upstream.read_line().await?);
+            writer
+                .write_all(response.as_bytes())
+                .await
+                .map_err(|source| Error::Io { step, source })?;
+        }
+    }
+
+    fn rewrite(&self, step: Step, line: &str) -> String {
+        let line = match log_response(step, line) {
+            Some(mut ticket) => {
+                for hook in &self.hooks {
+                    ticket = hook.launch(ticket);
+                }
+                ticket.to_string()
+            }
+            None => line.to_owned(),
+        };
+
+        self.hooks
+            .iter()
+            .fold(line, |line, hook| hook.response(step, line))
+    }
+}
+
+/// Logs a response as whatever `step` says it should be, handing back launch tickets.
+fn log_response(step: Step, line: &str) -> Option<L<'_>> {
+    match step {
+        Step::HashKey => {
+            log::<K>(step, line);
+        }
+        Step::Login => {
+            log::<A>(step, line);
+        }
+        Step::Games => {
+            log::<M>(step, line);
+        }
+        Step::GameInfo => {
+            log::<N>(step, line);
+        }
+        Step::Subscription => {
+            log::<F>(step, line);
+        }
+        Step::SelectGame => {
+            log::<G>(step, line);
+        }
+        Step::P => {
+            log::<P>(step, line);
+        }
+        Step::Characters => {
+            log::<C>(step, line);
+        }
+        Step::Launch => return log::<L>(step, line),
+        Step::Connect | Step::Other => tracing::debug!(%step, "response"),
+    }
+    None
+}
+
+/// Parses and logs a response, which is only ever logged through its redacting `Debug`.
+fn log<'a, T: Message<'a> + fmt::Debug>(step: Step, line: &'a str) -> Option<T> {
+    match T::parse(line) {
+        Ok(res) => {
+            tracing::debug!(%step, ?res, "response");
+            Some(res)
+        }
+        Err(e) => {
+            tracing::warn!(%step, error = %e, "passing on a response that didn't parse");
+            None
+        }
+    }
+}
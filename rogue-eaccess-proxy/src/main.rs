@@ -0,0 +1,67 @@
+use std::time::Duration;
+
+use clap::Parser;
+use eaccess::Config;
+use rogue_eaccess_proxy::{Proxy, Redirect};
+use tokio::net::TcpListener;
+
+/// Listen locally as if we were eaccess, and forward to the real one.
+///
+/// Point the frontend (or its launcher) at --listen. With --game-host and --game-port, launch
+/// tickets are rewritten so the frontend connects there instead of to the game.
+#[derive(Parser)]
+#[command(version)]
+struct Cli {
+    /// address to listen on
+    #[arg(long, default_value = "127.0.0.1:7900")]
+    listen: String,
+    /// eaccess server to forward to, as host:port
+    #[arg(long, default_value = "eaccess.play.net:7900")]
+    upstream: String,
+    /// game host to put in launch tickets
+    #[arg(long, requires = "game_port")]
+    game_host: Option<String>,
+    /// game port to put in launch tickets
+    #[arg(long, requires = "game_host")]
+    game_port: Option<u64>,
+    /// seconds to wait for each response from eaccess
+    #[arg(long, default_value_t = 15)]
+    step_timeout: u64,
+}
+
+#[tokio::main]
+async fn main() -> anyhow::Result<()> {
+    setup_tracing()?;
+
+    let cli = Cli::parse();
+    let mut proxy = Proxy::new(cli.upstream).with_config(Config {
+        step_timeout: Duration::from_secs(cli.step_timeout),
+        ..Config::default()
+    });
+    if let (Some(game_host), Some(game_port)) = (cli.game_host, cli.game_port) {
+        proxy = proxy.hook(Redirect {
+            game_host,
+            game_port,
+        });
+    }
+
+    let listener = TcpListener::bind(&cli.listen).await?;
+    tracing::info!("listening on {}", listener.local_addr()?);
+    proxy.serve(listener).await?;
+
+    Ok(())
+}
+
+fn setup_tracing() -> anyhow::Result<()> {
+    use tracing::subscriber::set_global_default;
+    use tracing_subscriber::{EnvFilter, fmt::Subscriber};
+
+    set_global_default(
+        Subscriber::builder()
+            .with_env_filter(EnvFilter::try_from_default_env().unwrap_or_else(|_| "info".into()))
+            .with_writer(std::io::stderr)
+            .finish(),
+    )?;
+
+    Ok(())
+}
@@ -0,0 +1,91 @@
+use eaccess::{Client, GameCode, NProtocol, Step};
+use rogue_eaccess_proxy::{Hook, Proxy, Redirect};
+use tokio::{
+    io::{AsyncBufReadExt, AsyncWriteExt, BufReader},
+    net::TcpListener,
+};
+
+/// Answers each request by its first letter, on one connection.
+async fn mock_eaccess() -> String {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap().to_string();
+
+    tokio::spawn(async move {
+        let (stream, _) = listener.accept().await.unwrap();
+        let mut stream = BufReader::new(stream);
+        let mut line = Vec::new();
+        while stream.read_until(b'\n', &mut line).await.unwrap() > 0 {
+            let response = match line[0] {
+                b'K' => "IKJPXQOEHDGHMPBIQLAJQSBTAMWFRMVS\n",
+                b'A' => "A\tACCOUNT\tKEY\t4e2a7ad3\tSome Person\n",
+                b'G' => "G\tGemStone IV\tFREE_TO_PLAY\t0\t\tROOT=sgc/gs\n",
+                b'C' => "C\t1\t5\t1\t1\tW_ACCOUNT_000\tFoo\n",
+                b'L' => {
+                    "L\tOK\tUPPORT=5535\tGAME=STORM\tGAMECODE=GS3\tFULLGAMENAME=Wrayth\tGAMEFILE=WRAYTH.EXE\tGAMEHOST=storm.gs4.game.play.net\tGAMEPORT=10024\tKEY=4e2a7ad3f1c90e6b\n"
+                }
+                _ => "?\tunknown request\n",
+            };
+            stream.write_all(response.as_bytes()).await.unwrap();
+            line.clear();
+        }
+    });
+
+    addr
+}
+
+async fn proxy(proxy: Proxy) -> String {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap().to_string();
+    tokio::spawn(proxy.serve(listener));
+    addr
+}
+
+#[tokio::test]
+async fn launches_are_redirected() {
+    let addr = proxy(Proxy::new(mock_eaccess().await).hook(Redirect {
+        game_host: "127.0.0.1".to_owned(),
+        game_port: 8999,
+    }))
+    .await;
+
+    let mut client = Client::connect_to(addr).await.unwrap();
+    client.login("ACCOUNT", "hunter2").await.unwrap();
+    assert_eq!(
+        client
+            .characters(&GameCode::GemStone)
+            .await
+            .unwrap()
+            .num_characters,
+        1
+    );
+
+    let ticket = client
+        .launch("W_ACCOUNT_000", NProtocol::Storm)
+        .await
+        .unwrap();
+    assert_eq!((ticket.game_host, ticket.game_port), ("127.0.0.1", 8999));
+    assert_eq!(ticket.key, "4e2a7ad3f1c90e6b");
+    assert_eq!(ticket.game_code, "GS3");
+}
+
+struct Shout;
+
+impl Hook for Shout {
+    fn response(&self, step: Step, line: String) -> String {
+        match step {
+            Step::Other => line.to_uppercase(),
+            _ => line,
+        }
+    }
+}
+
+#[tokio::test]
+async fn unparsed_responses_pass_through_hooks() {
+    let addr = proxy(Proxy::new(mock_eaccess().await).hook(Shout)).await;
+
+    let mut client = Client::connect_to(addr).await.unwrap();
+    client.send("x\n").await.unwrap();
+    assert_eq!(client.read_line().await.unwrap(), "?\tUNKNOWN REQUEST\n");
+    client.send("L\tnobody\tSTORM\n").await.unwrap();
+    assert!(client.read_line().await.unwrap().starts_with("L\tOK\t"));
+}
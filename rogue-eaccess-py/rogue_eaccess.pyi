@@ -0,0 +1,35 @@
+from os import PathLike
+
+class EaccessError(Exception): ...
+
+class Session:
+    @staticmethod
+    def login(
+        account: str,
+        password: str,
+        server: str | None = None,
+        step_timeout: float = 15.0,
+    ) -> Session: ...
+    def games(self) -> M: ...
+    def characters(self, game: str) -> C: ...
+    def launch(self, character: str, protocol: str = "STORM") -> L: ...
+
+class M:
+    games: list[tuple[str, str]]
+
+class C:
+    num_characters: int
+    max_characters: int
+    characters: list[tuple[str, str]]
+
+class L:
+    upport: int
+    game: str
+    game_code: str
+    full_game_name: str
+    game_file: str
+    game_host: str
+    game_port: int
+    key: str
+    def to_sal(self) -> str: ...
+    def write_sal(self, path: str | PathLike[str]) -> None: ...
@@ -0,0 +1,229 @@
+//! Python bindings for the eaccess client, built with maturin as the `rogue_eaccess` module.
+//!
+//! The session blocks; each call runs the async client on a runtime of its own, with the GIL
+//! released. Results are copied out into owned classes, since the Rust ones borrow from the
+//! client's read buffer.
+
+use std::{path::PathBuf, time::Duration};
+
+use eaccess::{Client, Config, ENDPOINT, Error, NProtocol};
+use pyo3::{
+    create_exception,
+    exceptions::{PyException, PyTimeoutError, PyValueError},
+    prelude::*,
+};
+use tokio::runtime::{Builder, Runtime};
+
+create_exception!(rogue_eaccess, EaccessError, PyException);
+
+fn to_py(e: Error) -> PyErr {
+    match e {
+        Error::Io { source, .. } => source.into(),
+        Error::Timeout(_) | Error::Deadline => PyTimeoutError::new_err(e.to_string()),
+        e => EaccessError::new_err(e.to_string()),
+    }
+}
+
+/// A logged in eaccess connection.
+#[pyclass(module = "rogue_eaccess")]
+struct Session {
+    runtime: Runtime,
+    client: Client,
+}
+
+#[pymethods]
+impl Session {
+    /// Connects to `server` (host:port, play.net's by default) and logs in.
+    #[staticmethod]
+    #[pyo3(signature = (account, password, server = None, step_timeout = 15.0))]
+    fn login(
+        py: Python<'_>,
+        account: &str,
+        password: &str,
+        server: Option<&str>,
+        step_timeout: f64,
+    ) -> PyResult<Self> {
+        let step_timeout = Duration::try_from_secs_f64(step_timeout)
+            .map_err(|e| PyValueError::new_err(e.to_string()))?;
+        let config = Config {
+            step_timeout,
+            ..Config::default()
+        };
+        let runtime = Builder::new_current_thread().enable_all().build()?;
+
+        let client = py
+            .allow_threads(|| {
+                runtime.block_on(async {
+                    match server {
+                        Some(server) => {
+                            Client::connect_and_login(server, account, password, config).await
+                        }
+                        None => {
+                            Client::connect_and_login(ENDPOINT, account, password, config).await
+                        }
+                    }
+                })
+            })
+            .map_err(to_py)?;
+
+        Ok(Self { runtime, client })
+    }
+
+    /// Lists the games on the account.
+    fn games(&mut self, py: Python<'_>) -> PyResult<M> {
+        let Self { runtime, client } = self;
+        py.allow_threads(|| runtime.block_on(async { client.games().await.map(M::from) }))
+            .map_err(to_py)
+    }
+
+    /// Selects `game` and lists its characters.
+    fn characters(&mut self, py: Python<'_>, game: &str) -> PyResult<C> {
+        let Self { runtime, client } = self;
+        py.allow_threads(|| {
+            runtime.block_on(async { client.characters(&game.into()).await.map(C::from) })
+        })
+        .map_err(to_py)
+    }
+
+    /// Gets a launch ticket for `character` (an id from `characters`) on the game last selected.
+    #[pyo3(signature = (character, protocol = "STORM"))]
+    fn launch(&mut self, py: Python<'_>, character: &str, protocol: &str) -> PyResult<L> {
+        let Self { runtime, client } = self;
+        py.allow_threads(|| {
+            runtime.block_on(async {
+                client
+                    .launch(character, NProtocol::from(protocol))
+                    .await
+                    .map(L::from)
+            })
+        })
+        .map_err(to_py)
+    }
+}
+
+/// The games on the account, as `(code, name)` pairs.
+#[pyclass(module = "rogue_eaccess", frozen, get_all)]
+#[derive(Debug)]
+struct M {
+    games: Vec<(String, String)>,
+}
+
+impl From<eaccess::M<'_>> for M {
+    fn from(value: eaccess::M<'_>) -> Self {
+        Self {
+            games: pairs(&value.0),
+        }
+    }
+}
+
+#[pymethods]
+impl M {
+    fn __repr__(&self) -> String {
+        format!("{self:?}")
+    }
+}
+
+/// The characters on a game, as `(id, name)` pairs.
+#[pyclass(module = "rogue_eaccess", frozen, get_all)]
+#[derive(Debug)]
+struct C {
+    num_characters: u64,
+    max_characters: u64,
+    characters: Vec<(String, String)>,
+}
+
+impl From<eaccess::C<'_>> for C {
+    fn from(value: eaccess::C<'_>) -> Self {
+        Self {
+            num_characters: value.num_characters,
+            max_characters: value.max_characters,
+            characters: pairs(&value.characters),
+        }
+    }
+}
+
+#[pymethods]
+impl C {
+    fn __repr__(&self) -> String {
+        format!("{self:?}")
+    }
+}
+
+/// A launch ticket.
+#[pyclass(module = "rogue_eaccess", frozen, get_all)]
+struct L {
+    upport: u64,
+    game: String,
+    game_code: String,
+    full_game_name: String,
+    game_file: String,
+    game_host: String,
+    game_port: u64,
+    key: String,
+}
+
+impl L {
+    fn borrowed(&self) -> eaccess::L<'_> {
+        eaccess::L {
+            upport: self.upport,
+            game: &self.game,
+            game_code: &self.game_code,
+            full_game_name: &self.full_game_name,
+            game_file: &self.game_file,
+            game_host: &self.game_host,
+            game_port: self.game_port,
+            key: &self.key,
+        }
+    }
+}
+
+impl From<eaccess::L<'_>> for L {
+    fn from(value: eaccess::L<'_>) -> Self {
+        Self {
+            upport: value.upport,
+            game: value.game.to_owned(),
+            game_code: value.game_code.to_owned(),
+            full_game_name: value.full_game_name.to_owned(),
+            game_file: value.game_file.to_owned(),
+            game_host: value.game_host.to_owned(),
+            game_port: value.game_port,
+            key: value.key.to_owned(),
+        }
+    }
+}
+
+#[pymethods]
+impl L {
+    /// The ticket in the `.sal` launch file format.
+    fn to_sal(&self) -> String {
+        self.borrowed().to_sal()
+    }
+
+    /// Writes the ticket to a `.sal` file at `path`.
+    fn write_sal(&self, path: PathBuf) -> PyResult<()> {
+        Ok(std::fs::write(path, self.to_sal())?)
+    }
+
+    /// Leaves out the key, like the Rust `Debug` output.
+    fn __repr__(&self) -> String {
+        format!("{:?}", self.borrowed())
+    }
+}
+
+fn pairs(items: &[(&str, &str)]) -> Vec<(String, String)> {
+    items
+        .iter()
+        .map(|(a, b)| (a.to_string(), b.to_string()))
+        .collect()
+}
+
+#[pymodule]
+#[pyo3(name = "rogue_eaccess")]
+fn rogue_eaccess_py(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    m.add_class::<Session>()?;
+    m.add_class::<M>()?;
+    m.add_class::<C>()?;
+    m.add_class::<L>()?;
+    m.add("EaccessError", m.py().get_type::<EaccessError>())?;
+    Ok(())
+}
@@ -0,0 +1,51 @@
+"""A mock eaccess server that answers every request with a canned response."""
+
+import socketserver
+import threading
+
+import pytest
+
+HASH_KEY = "IKJPXQOEHDGHMPBIQLAJQSBTAMWFRMVS"
+
+RESPONSES = {
+    "K": HASH_KEY + "\n",
+    "A": "A\tACCOUNT\tKEY\t4e2a7ad3\tSome Person\n",
+    "M": "M\tGS3\tGemStone IV\tDR\tDragonRealms\n",
+    "G": "G\tGemStone IV\tFREE_TO_PLAY\t0\t\tROOT=sgc/gs\tMKTG=info/default.jsp\n",
+    "C": "C\t2\t5\t1\t1\tW_ACCOUNT_000\tFoo\tW_ACCOUNT_001\tBar\n",
+    "L": "L\tOK\tUPPORT=5535\tGAME=STORM\tGAMECODE=GS3\tFULLGAMENAME=Wrayth\tGAMEFILE=WRAYTH.EXE"
+    "\tGAMEHOST=storm.gs4.game.play.net\tGAMEPORT=10024\tKEY=4e2a7ad3f1c90e6b\n",
+}
+
+
+class Handler(socketserver.StreamRequestHandler):
+    def handle(self):
+        for line in self.rfile:
+            self.server.requests.append(line)
+            # the real server's answer to a bad password
+            if line.startswith(b"A\tLOCKED\t"):
+                self.wfile.write(b"A\t\tPASSWORD\n")
+                continue
+            self.wfile.write(RESPONSES.get(chr(line[0]), "X\n").encode())
+
+
+class Server(socketserver.ThreadingTCPServer):
+    daemon_threads = True
+
+    def __init__(self):
+        super().__init__(("127.0.0.1", 0), Handler)
+        self.requests = []
+
+    @property
+    def address(self):
+        host, port = self.server_address
+        return f"{host}:{port}"
+
+
+@pytest.fixture
+def server():
+    with Server() as server:
+        thread = threading.Thread(target=server.serve_forever, daemon=True)
+        thread.start()
+        yield server
+        server.shutdown()
@@ -0,0 +1,56 @@
+"""Run with `maturin develop && pytest` from the rogue-eaccess-py directory."""
+
+import pytest
+
+import rogue_eaccess
+from conftest import HASH_KEY
+
+
+def login(server, account="ACCOUNT"):
+    return rogue_eaccess.Session.login(account, "hunter2", server=server.address, step_timeout=5)
+
+
+def test_login_sends_the_hashed_password(server):
+    login(server)
+
+    hashed = bytes(((p - 0x20) ^ h) + 0x20 for p, h in zip(b"hunter2", HASH_KEY.encode()))
+    assert server.requests[:2] == [b"K\n", b"A\tACCOUNT\t" + hashed + b"\n"]
+
+
+def test_bad_logins_raise(server):
+    with pytest.raises(rogue_eaccess.EaccessError):
+        login(server, account="LOCKED")
+
+
+def test_games(server):
+    games = login(server).games()
+
+    assert isinstance(games, rogue_eaccess.M)
+    assert games.games == [("GS3", "GemStone IV"), ("DR", "DragonRealms")]
+
+
+def test_characters(server):
+    characters = login(server).characters("GS3")
+
+    assert isinstance(characters, rogue_eaccess.C)
+    assert characters.num_characters == 2
+    assert characters.max_characters == 5
+    assert characters.characters == [("W_ACCOUNT_000", "Foo"), ("W_ACCOUNT_001", "Bar")]
+    assert server.requests[-2:] == [b"G\tGS3\n", b"C\n"]
+
+
+def test_launch_writes_a_sal_file(server, tmp_path):
+    session = login(server)
+    session.characters("GS3")
+    ticket = session.launch("W_ACCOUNT_000")
+
+    assert isinstance(ticket, rogue_eaccess.L)
+    assert (ticket.game_host, ticket.game_port) == ("storm.gs4.game.play.net", 10024)
+    assert ticket.key not in repr(ticket)
+    assert server.requests[-1] == b"L\tW_ACCOUNT_000\tSTORM\n"
+
+    ticket.write_sal(tmp_path / "launch.sal")
+    sal = (tmp_path / "launch.sal").read_text()
+    assert sal == ticket.to_sal()
+    assert "GAMEHOST=storm.gs4.game.play.net\n" in sal
+    assert "KEY=4e2a7ad3f1c90e6b\n" in sal
@@ -0,0 +1,103 @@
+//! JavaScript bindings for the eaccess parsers, for launchers that reach eaccess through a
+//! websocket-to-TCP bridge instead of a socket of their own.
+//!
+//! Responses come back as plain objects with the same field names as the Rust types; requests go
+//! out as `Uint8Array`s, ready to be sent as they are.
+
+use eaccess::{A, C, F, G, K, L, M, Message, N, NProtocol, P, Request};
+use serde::Serialize;
+use serde_wasm_bindgen::Serializer;
+use wasm_bindgen::prelude::*;
+
+/// Hashes a password with the key from the K response.
+#[wasm_bindgen(js_name = hashPassword)]
+pub fn hash_password(password: &[u8], hash_key: &[u8]) -> Vec<u8> {
+    eaccess::hash_password(password.iter().copied(), hash_key.iter().copied()).collect()
+}
+
+fn parse<'a, T: Message<'a> + Serialize>(line: &'a str) -> Result<JsValue, JsError> {
+    let message = T::parse(line).map_err(|e| JsError::new(&e.to_string()))?;
+    // json_compatible so the numbers come out as numbers rather than BigInts
+    Ok(message.serialize(&Serializer::json_compatible())?)
+}
+
+macro_rules! parsers {
+    ($($ty:ident: $name:ident => $js_name:ident;)*) => {
+        $(
+            #[doc = concat!("Parses a ", stringify!($ty), " response, trailing newline included.")]
+            #[wasm_bindgen(js_name = $js_name)]
+            pub fn $name(line: &str) -> Result<JsValue, JsError> {
+                parse::<$ty>(line)
+            }
+        )*
+    };
+}
+
+parsers! {
+    K: parse_k => parseK;
+    A: parse_a => parseA;
+    M: parse_m => parseM;
+    N: parse_n => parseN;
+    F: parse_f => parseF;
+    G: parse_g => parseG;
+    P: parse_p => parseP;
+    C: parse_c => parseC;
+    L: parse_l => parseL;
+}
+
+#[wasm_bindgen(js_name = requestK)]
+pub fn request_k() -> Vec<u8> {
+    Request::K.to_bytes()
+}
+
+/// `hashed_password` is the output of [`hash_password`].
+#[wasm_bindgen(js_name = requestA)]
+pub fn request_a(account: &str, hashed_password: &[u8]) -> Vec<u8> {
+    Request::A {
+        account,
+        hashed_password,
+    }
+    .to_bytes()
+}
+
+#[wasm_bindgen(js_name = requestM)]
+pub fn request_m() -> Vec<u8> {
+    Request::M.to_bytes()
+}
+
+#[wasm_bindgen(js_name = requestN)]
+pub fn request_n(game: &str) -> Vec<u8> {
+    Request::N { game: game.into() }.to_bytes()
+}
+
+#[wasm_bindgen(js_name = requestF)]
+pub fn request_f(game: &str) -> Vec<u8> {
+    Request::F { game: game.into() }.to_bytes()
+}
+
+#[wasm_bindgen(js_name = requestG)]
+pub fn request_g(game: &str) -> Vec<u8> {
+    Request::G { game: game.into() }.to_bytes()
+}
+
+#[wasm_bindgen(js_name = requestP)]
+pub fn request_p(game: &str) -> Vec<u8> {
+    Request::P { game: game.into() }.to_bytes()
+}
+
+#[wasm_bindgen(js_name = requestC)]
+pub fn request_c() -> Vec<u8> {
+    Request::C.to_bytes()
+}
+
+/// `protocol` defaults to `STORM`.
+#[wasm_bindgen(js_name = requestL)]
+pub fn request_l(character: &str, protocol: Option<String>) -> Vec<u8> {
+    Request::L {
+        character,
+        protocol: protocol
+            .as_deref()
+            .map_or(NProtocol::Storm, NProtocol::from),
+    }
+    .to_bytes()
+}
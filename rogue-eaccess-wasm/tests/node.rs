@@ -0,0 +1,64 @@
+//! Run with `cargo test -p rogue-eaccess-wasm --target wasm32-unknown-unknown`, which needs
+//! `wasm-bindgen-cli` and node installed.
+
+#![cfg(target_arch = "wasm32")]
+
+use js_sys::Reflect;
+use rogue_eaccess_wasm::*;
+use wasm_bindgen::JsValue;
+use wasm_bindgen_test::wasm_bindgen_test;
+
+const L_LINE: &str = "L\tOK\tUPPORT=5535\tGAME=STORM\tGAMECODE=GS3\tFULLGAMENAME=Wrayth\tGAMEFILE=WRAYTH.EXE\tGAMEHOST=storm.gs4.game.play.net\tGAMEPORT=10024\tKEY=4e2a7ad3f1c90e6b\n";
+
+fn get(value: &JsValue, field: &str) -> JsValue {
+    Reflect::get(value, &field.into()).unwrap()
+}
+
+#[wasm_bindgen_test]
+fn parses_launch_tickets() {
+    let l = parse_l(L_LINE).unwrap();
+    assert_eq!(get(&l, "game_host"), "storm.gs4.game.play.net");
+    assert_eq!(get(&l, "game_port"), 10024);
+    assert_eq!(get(&l, "key"), "4e2a7ad3f1c90e6b");
+}
+
+#[wasm_bindgen_test]
+fn parses_payment_status_as_its_wire_string() {
+    assert_eq!(parse_f("F\tNEED_BILL\n").unwrap(), "NEED_BILL");
+}
+
+#[wasm_bindgen_test]
+fn rejects_garbage() {
+    assert!(parse_l("L\tPROBLEM\n").is_err());
+    assert!(parse_c("C\tx\n").is_err());
+}
+
+#[wasm_bindgen_test]
+fn hashing_twice_with_the_same_key_gives_the_password_back() {
+    let key = b"IKJPXQOEHDGHMPBI";
+    let hashed = hash_password(b"hunter2", key);
+    assert_ne!(hashed, b"hunter2");
+    assert_eq!(hash_password(&hashed, key), b"hunter2");
+}
+
+#[wasm_bindgen_test]
+fn hashes_control_characters() {
+    let key = b"IKJPXQOEHDGHMPBI";
+    let hashed = hash_password(b"\x01\tpass\x7f\xff", key);
+    assert_eq!(hash_password(&hashed, key), b"\x01\tpass\x7f\xff");
+}
+
+#[wasm_bindgen_test]
+fn serializes_requests() {
+    assert_eq!(request_k(), b"K\n");
+    assert_eq!(request_a("ACCOUNT", b"hashed"), b"A\tACCOUNT\thashed\n");
+    assert_eq!(request_g("GS3"), b"G\tGS3\n");
+    assert_eq!(
+        request_l("W_ACCOUNT_000", None),
+        b"L\tW_ACCOUNT_000\tSTORM\n"
+    );
+    assert_eq!(
+        request_l("W_ACCOUNT_000", Some("OTHER".into())),
+        b"L\tW_ACCOUNT_000\tOTHER\n"
+    );
+}
@@ -0,0 +1,140 @@
+//! Throughput and allocation counts of `Message::parse`, for both the chumsky and nom backends.
+//!
+//! Run with `cargo bench -p rogue-eaccess --features nom`. Allocation counts are printed once per
+//! input before it is measured.
+
+use std::{
+    alloc::{GlobalAlloc, Layout, System},
+    fmt::Write,
+    hint::black_box,
+    sync::atomic::{AtomicUsize, Ordering},
+};
+
+use criterion::{Criterion, Throughput, criterion_group, criterion_main};
+use rogue_eaccess::{A, C, F, G, K, L, M, N, P, nom};
+
+struct Counting;
+
+static ALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for Counting {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        unsafe { System.alloc(layout) }
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        unsafe { System.dealloc(ptr, layout) }
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        unsafe { System.realloc(ptr, layout, new_size) }
+    }
+}
+
+#[global_allocator]
+static GLOBAL: Counting = Counting;
+
+fn allocations(f: impl FnOnce()) -> usize {
+    let before = ALLOCATIONS.load(Ordering::Relaxed);
+    f();
+    ALLOCATIONS.load(Ordering::Relaxed) - before
+}
+
+macro_rules! bench_message {
+    ($c:expr, $ty:ident, $name:expr, $line:expr) => {{
+        let name: &str = $name;
+        let line: &str = $line;
+
+        // make sure we're measuring a successful parse, not an early error
+        <$ty as rogue_eaccess::Message>::parse(line).unwrap();
+        <$ty as nom::Message>::parse(line).unwrap();
+
+        eprintln!(
+            "{name}: {} bytes, chumsky {} allocations, nom {} allocations",
+            line.len(),
+            allocations(|| drop(black_box(<$ty as rogue_eaccess::Message>::parse(line)))),
+            allocations(|| drop(black_box(<$ty as nom::Message>::parse(line)))),
+        );
+
+        let mut group = $c.benchmark_group(name);
+        group.throughput(Throughput::Bytes(line.len() as u64));
+        group.bench_function("chumsky", |b| {
+            b.iter(|| <$ty as rogue_eaccess::Message>::parse(black_box(line)))
+        });
+        group.bench_function("nom", |b| {
+            b.iter(|| <$ty as nom::Message>::parse(black_box(line)))
+        });
+        group.finish();
+    }};
+}
+
+const GAMES: &[(&str, &str)] = &[
+    ("DR", "DragonRealms"),
+    ("DRD", "DragonRealms Development"),
+    ("DRF", "DragonRealms The Fallen"),
+    ("DRT", "DragonRealms Prime Test"),
+    ("DRX", "DragonRealms Platinum"),
+    ("GS3", "GemStone IV"),
+    ("GS4D", "GemStone IV Development"),
+    ("GSF", "GemStone IV Shattered"),
+    ("GST", "GemStone IV Prime Test"),
+    ("GSX", "GemStone IV Platinum"),
+];
+
+fn m_line(games: usize) -> String {
+    let mut line = String::from("M");
+    for i in 0..games {
+        let (code, name) = GAMES[i % GAMES.len()];
+        write!(line, "\t{code}{i}\t{name} {i}").unwrap();
+    }
+    line.push('\n');
+    line
+}
+
+fn c_line(characters: usize) -> String {
+    let mut line = format!("C\t{characters}\t{characters}\t1\t1");
+    for i in 0..characters {
+        write!(line, "\tW_ACCOUNT_{i:03}\tCharacter{i}").unwrap();
+    }
+    line.push('\n');
+    line
+}
+
+fn g_line(links: usize) -> String {
+    let mut line = String::from("G\tGemStone IV\tFREE_TO_PLAY\t0\t\tROOT=sgc/gs");
+    for i in 0..links {
+        write!(line, "\tLINK{i}=link{i}/default.jsp").unwrap();
+    }
+    line.push('\n');
+    line
+}
+
+fn bench(c: &mut Criterion) {
+    bench_message!(c, K, "k", "IKJPXQOEHDGHMPBIQLAJQSBTAMWFRMVS\n");
+    bench_message!(
+        c,
+        A,
+        "a",
+        "A\tACCOUNT\tKEY\t4e2a7ad3f1c90e6b8b1d5f3c2a9e7d10\tSome Person\n"
+    );
+    bench_message!(c, M, "m", &m_line(GAMES.len()));
+    bench_message!(c, M, "m/500", &m_line(500));
+    bench_message!(c, N, "n", "N\tPRODUCTION|STORM\n");
+    bench_message!(c, F, "f", "F\tFREE_TO_PLAY\n");
+    bench_message!(c, G, "g", &g_line(12));
+    bench_message!(c, G, "g/200", &g_line(200));
+    bench_message!(c, P, "p", "P\tGS3\t1495\t1\t1495\t1\t1\n");
+    bench_message!(c, C, "c", &c_line(3));
+    bench_message!(c, C, "c/50", &c_line(50));
+    bench_message!(
+        c,
+        L,
+        "l",
+        "L\tOK\tUPPORT=5535\tGAME=STORM\tGAMECODE=GS3\tFULLGAMENAME=Wrayth\tGAMEFILE=WRAYTH.EXE\tGAMEHOST=storm.gs4.game.play.net\tGAMEPORT=10024\tKEY=4e2a7ad3f1c90e6b8b1d5f3c2a9e7d10\n"
+    );
+}
+
+criterion_group!(benches, bench);
+criterion_main!(benches);
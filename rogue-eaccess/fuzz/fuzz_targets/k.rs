@@ -0,0 +1,7 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|data: &str| {
+    rogue_eaccess_fuzz::check::<rogue_eaccess::K>(data);
+});
@@ -0,0 +1,11 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use rogue_eaccess::fuzzing;
+
+fuzz_target!(|data: &str| {
+    assert_eq!(
+        fuzzing::number(data),
+        data.parse().ok().filter(|_| !data.starts_with('+'))
+    );
+});
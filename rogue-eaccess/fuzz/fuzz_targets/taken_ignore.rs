@@ -0,0 +1,15 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use rogue_eaccess::fuzzing;
+
+fuzz_target!(|data: (char, &str)| {
+    let (delimiter, inp) = data;
+
+    if let Some((taken, rest)) = fuzzing::taken_ignore(inp, delimiter) {
+        assert!(!taken.contains(delimiter));
+        assert_eq!(format!("{taken}{delimiter}{rest}"), inp);
+    } else {
+        assert!(!inp.contains(delimiter));
+    }
+});
@@ -0,0 +1,22 @@
+use std::fmt::Debug;
+
+use rogue_eaccess::{Message, nom};
+
+/// Parses `data` with both backends. Neither may panic, and they must agree on whether the line
+/// parses and, when it does, on the message it holds.
+pub fn check<'a, T>(data: &'a str)
+where
+    T: Message<'a> + nom::Message<'a> + PartialEq + Debug,
+{
+    let chumsky = <T as Message>::parse(data);
+    let nom = <T as nom::Message>::parse(data);
+
+    assert_eq!(
+        chumsky.is_ok(),
+        nom.is_ok(),
+        "chumsky: {chumsky:?}, nom: {nom:?}"
+    );
+    if let (Ok(chumsky), Ok(nom)) = (chumsky, nom) {
+        assert_eq!(chumsky, nom);
+    }
+}
@@ -0,0 +1,283 @@
+//! An async client for the eaccess login server.
+//!
+//! Every response borrows from the client's line buffer, so it has to be dropped (or copied out
+//! of) before the next request is made.
+
+use std::{fmt, time::Duration};
+
+use tokio::{
+    io::{AsyncBufReadExt, AsyncRead, AsyncWrite, AsyncWriteExt, BufReader},
+    net::{TcpStream, ToSocketAddrs},
+    time::timeout,
+};
+use tracing::Instrument;
+
+use crate::{
+    A, C, ENDPOINT, Error, G, GameCode, K, L, M, Message, NProtocol, Redacted, Retry,
+    hash_password, retry,
+};
+
+#[derive(Debug, Clone)]
+pub struct Config {
+    /// how long to wait for the TCP connection
+    pub connect_timeout: Duration,
+    /// how long to wait for the response to any one request
+    pub step_timeout: Duration,
+    /// how long [`Client::connect_and_login`] may take overall, retries included
+    pub login_deadline: Option<Duration>,
+    pub retry: Retry,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            connect_timeout: Duration::from_secs(10),
+            step_timeout: Duration::from_secs(15),
+            login_deadline: Some(Duration::from_secs(60)),
+            retry: Retry::default(),
+        }
+    }
+}
+
+/// A step of the SGE conversation, named after what it's for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Step {
+    Connect,
+    /// K
+    HashKey,
+    /// A
+    Login,
+    /// M
+    Games,
+    /// N
+    GameInfo,
+    /// F
+    Subscription,
+    /// G
+    SelectGame,
+    /// P
+    P,
+    /// C
+    Characters,
+    /// L
+    Launch,
+    /// anything else, e.g. lines sent with [`Client::send`]
+    Other,
+}
+
+impl Step {
+    /// Works out the step from the first byte of an outgoing request.
+    pub fn of(request: &[u8]) -> Self {
+        match request.first() {
+            Some(b'K') => Self::HashKey,
+            Some(b'A') => Self::Login,
+            Some(b'M') => Self::Games,
+            Some(b'N') => Self::GameInfo,
+            Some(b'F') => Self::Subscription,
+            Some(b'G') => Self::SelectGame,
+            Some(b'P') => Self::P,
+            Some(b'C') => Self::Characters,
+            Some(b'L') => Self::Launch,
+            _ => Self::Other,
+        }
+    }
+
+    /// Whether the step can safely be done again from scratch. Only L hands out anything (a
+    /// launch ticket), so only L is unsafe; unknown requests are assumed unsafe too.
+    pub fn is_idempotent(self) -> bool {
+        !matches!(self, Self::Launch | Self::Other)
+    }
+}
+
+impl fmt::Display for Step {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Connect => "connect",
+            Self::HashKey => "hash key (K)",
+            Self::Login => "login (A)",
+            Self::Games => "game list (M)",
+            Self::GameInfo => "game info (N)",
+            Self::Subscription => "subscription (F)",
+            Self::SelectGame => "game selection (G)",
+            Self::P => "P",
+            Self::Characters => "character list (C)",
+            Self::Launch => "launch (L)",
+            Self::Other => "request",
+        })
+    }
+}
+
+pub struct Client<S = TcpStream> {
+    stream: BufReader<S>,
+    buf: String,
+    config: Config,
+}
+
+impl<S> fmt::Debug for Client<S> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        // the buffer holds the last response, which may well be a key
+        f.debug_struct("Client")
+            .field("buf", &Redacted(&self.buf))
+            .field("config", &self.config)
+            .finish_non_exhaustive()
+    }
+}
+
+impl Client {
+    /// Connects to the play.net eaccess server.
+    pub async fn connect() -> Result<Self, Error> {
+        Self::connect_to(ENDPOINT).await
+    }
+
+    pub async fn connect_to(addr: impl ToSocketAddrs) -> Result<Self, Error> {
+        Self::connect_with(addr, Config::default()).await
+    }
+
+    pub async fn connect_with(addr: impl ToSocketAddrs, config: Config) -> Result<Self, Error> {
+        let stream = timeout(config.connect_timeout, TcpStream::connect(addr))
+            .await
+            .map_err(|_| Error::Timeout(Step::Connect))?
+            .map_err(|source| Error::Io {
+                step: Step::Connect,
+                source,
+            })?;
+        tracing::debug!(peer = ?stream.peer_addr().ok(), "connected to eaccess");
+        Ok(Self::with_config(stream, config))
+    }
+
+    /// Connects and logs in, retrying transient failures on a fresh connection each time.
+    ///
+    /// The whole thing, retries and backoff included, is bounded by `config.login_deadline`.
+    pub async fn connect_and_login(
+        addr: impl ToSocketAddrs + Clone,
+        account: &str,
+        password: &str,
+        config: Config,
+    ) -> Result<Self, Error> {
+        let attempt = retry(&config.retry, || async {
+            let mut client = Self::connect_with(addr.clone(), config.clone()).await?;
+            client.login(account, password).await?;
+            Ok(client)
+        })
+        .instrument(tracing::info_span!("eaccess_login", account));
+
+        match config.login_deadline {
+            Some(deadline) => timeout(deadline, attempt)
+                .await
+                .map_err(|_| Error::Deadline)?,
+            None => attempt.await,
+        }
+    }
+}
+
+impl<S: AsyncRead + AsyncWrite + Unpin> Client<S> {
+    pub fn new(stream: S) -> Self {
+        Self::with_config(stream, Config::default())
+    }
+
+    pub fn with_config(stream: S, config: Config) -> Self {
+        Self {
+            stream: BufReader::new(stream),
+            buf: String::new(),
+            config,
+        }
+    }
+
+    pub fn config(&self) -> &Config {
+        &self.config
+    }
+
+    /// Writes a raw request without waiting for the response.
+    pub async fn send(&mut self, request: impl AsRef<[u8]>) -> Result<(), Error> {
+        let request = request.as_ref();
+        let step = Step::of(request);
+        timeout(self.config.step_timeout, self.stream.write_all(request))
+            .await
+            .map_err(|_| Error::Timeout(step))?
+            .map_err(|source| Error::Io { step, source })?;
+        Ok(())
+    }
+
+    /// Reads the next line from the server, including the trailing newline.
+    pub async fn read_line(&mut self) -> Result<&str, Error> {
+        self.read_line_for(Step::Other).await
+    }
+
+    async fn read_line_for(&mut self, step: Step) -> Result<&str, Error> {
+        self.buf.clear();
+        let read = timeout(
+            self.config.step_timeout,
+            self.stream.read_line(&mut self.buf),
+        )
+        .await
+        .map_err(|_| Error::Timeout(step))?
+        .map_err(|source| Error::Io { step, source })?;
+        if read == 0 {
+            return Err(Error::Closed(step));
+        }
+        Ok(&self.buf)
+    }
+
+    /// Sends `request` and parses the response as `T`.
+    ///
+    /// Only the step is logged, never the request or response themselves; log the parsed
+    /// response's `Debug` output if you need it.
+    pub async fn request<'a, T: Message<'a>>(
+        &'a mut self,
+        request: impl AsRef<[u8]>,
+    ) -> Result<T, Error> {
+        let request = request.as_ref();
+        let step = Step::of(request);
+        let span = tracing::debug_span!("eaccess_step", %step);
+
+        async {
+            self.send(request).await?;
+            let res = T::parse(self.read_line_for(step).await?);
+            match &res {
+                Ok(_) => tracing::trace!("ok"),
+                Err(e) => tracing::debug!(error = %e, "failed"),
+            }
+            res
+        }
+        .instrument(span)
+        .await
+    }
+
+    /// Asks for a hash key, then logs in with it.
+    pub async fn login(&mut self, account: &str, password: &str) -> Result<A<'_>, Error> {
+        tracing::debug!(account, password = ?Redacted(password), "logging in");
+        let key = self.request::<K>(K::out()).await?.key.to_owned();
+        let hashed = hash_password(password.bytes(), key.bytes());
+        let res = self.request::<A>(A::out(account.bytes(), hashed)).await?;
+        tracing::debug!(?res, "logged in");
+        Ok(res)
+    }
+
+    pub async fn games(&mut self) -> Result<M<'_>, Error> {
+        let res = self.request::<M>(M::out()).await?;
+        tracing::trace!(?res);
+        Ok(res)
+    }
+
+    /// Selects `game` with G, then lists its characters with C.
+    pub async fn characters(&mut self, game: &GameCode) -> Result<C<'_>, Error> {
+        let res = self.request::<G>(G::out(game)).await?;
+        tracing::trace!(?res);
+        let res = self.request::<C>(C::out()).await?;
+        tracing::trace!(?res);
+        Ok(res)
+    }
+
+    /// Requests a launch ticket for `character` on the game last selected with G.
+    ///
+    /// This is never retried: a timeout here may still have produced a ticket.
+    pub async fn launch<'a>(
+        &mut self,
+        character: &str,
+        protocol: NProtocol<'a>,
+    ) -> Result<L<'_>, Error> {
+        let res = self.request::<L>(L::out(character, protocol)).await?;
+        tracing::debug!(?res, "got launch ticket");
+        Ok(res)
+    }
+}
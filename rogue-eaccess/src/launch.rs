@@ -0,0 +1,148 @@
+use std::fmt;
+
+use tokio::io::{AsyncRead, AsyncWrite};
+
+use crate::{Client, Error, GameCode, L, NProtocol, Redacted};
+
+/// A launch ticket copied out of an L response, so it can outlive the next request.
+#[derive(Clone, PartialEq, Eq)]
+pub struct Ticket {
+    /// the id of the character the ticket is for
+    pub character: String,
+    pub upport: u64,
+    pub game: String,
+    pub game_code: String,
+    pub full_game_name: String,
+    pub game_file: String,
+    pub game_host: String,
+    pub game_port: u64,
+    pub key: String,
+}
+
+impl Ticket {
+    fn new(character: &str, l: &L<'_>) -> Self {
+        Self {
+            character: character.to_owned(),
+            upport: l.upport,
+            game: l.game.to_owned(),
+            game_code: l.game_code.to_owned(),
+            full_game_name: l.full_game_name.to_owned(),
+            game_file: l.game_file.to_owned(),
+            game_host: l.game_host.to_owned(),
+            game_port: l.game_port,
+            key: l.key.to_owned(),
+        }
+    }
+
+    pub fn as_l(&self) -> L<'_> {
+        L {
+            upport: self.upport,
+            game: &self.game,
+            game_code: &self.game_code,
+            full_game_name: &self.full_game_name,
+            game_file: &self.game_file,
+            game_host: &self.game_host,
+            game_port: self.game_port,
+            key: &self.key,
+        }
+    }
+}
+
+impl fmt::Debug for Ticket {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Ticket")
+            .field("character", &self.character)
+            .field("game_code", &self.game_code)
+            .field("game_host", &self.game_host)
+            .field("game_port", &self.game_port)
+            .field("key", &Redacted(&self.key))
+            .finish_non_exhaustive()
+    }
+}
+
+impl<S: AsyncRead + AsyncWrite + Unpin> Client<S> {
+    /// Launches several characters in one session, possibly across games, and returns their
+    /// tickets in the same order.
+    ///
+    /// `launches` are `(game code, character)` pairs, where the character is an id or a name
+    /// (case-insensitive). Every character is looked up before anything is launched, so an
+    /// unknown one fails the whole call without using up any tickets. The launches are then
+    /// grouped by game, so each game is selected at most once more. If a launch fails after
+    /// that, the error is [`Error::PartialLaunch`], holding the tickets issued so far, still in
+    /// the order of `launches`. Like [`Client::launch`], this is never retried.
+    pub async fn launch_all(
+        &mut self,
+        launches: &[(GameCode, &str)],
+        protocol: NProtocol<'_>,
+    ) -> Result<Vec<Ticket>, Error> {
+        let mut rosters: Vec<(&GameCode, Vec<(String, String)>)> = Vec::new();
+        let mut ids = Vec::with_capacity(launches.len());
+        for (game, character) in launches {
+            if !rosters.iter().any(|(g, _)| *g == game) {
+                let res = self.characters(game).await?;
+                let characters = res
+                    .characters
+                    .into_iter()
+                    .map(|(id, name)| (id.to_owned(), name.to_owned()))
+                    .collect();
+                rosters.push((game, characters));
+            }
+            let (_, characters) = rosters.iter().find(|(g, _)| *g == game).unwrap();
+            let Some((id, _)) = characters
+                .iter()
+                .find(|(id, name)| id == character || name.eq_ignore_ascii_case(character))
+            else {
+                return Err(Error::UnknownCharacter {
+                    game: game.clone(),
+                    character: (*character).to_owned(),
+                });
+            };
+            ids.push(id.clone());
+        }
+
+        // L goes to whichever game was last selected with G, which is now the last one looked
+        // up, so its characters go first and every other game is selected once
+        let games = rosters.iter().rev().map(|(game, _)| *game);
+        let mut tickets: Vec<Option<Ticket>> = vec![None; launches.len()];
+        for (n, game) in games.enumerate() {
+            let res = self
+                .launch_game(game, n > 0, launches, &ids, &mut tickets, protocol.clone())
+                .await;
+            match res {
+                Ok(()) => {}
+                Err(source) if tickets.iter().all(Option::is_none) => return Err(source),
+                Err(source) => {
+                    return Err(Error::PartialLaunch {
+                        tickets: tickets.into_iter().flatten().collect(),
+                        source: Box::new(source),
+                    });
+                }
+            }
+        }
+
+        Ok(tickets.into_iter().flatten().collect())
+    }
+
+    /// Selects `game` if `select`, then launches every character on it, putting each ticket in
+    /// the same slot as its launch.
+    async fn launch_game(
+        &mut self,
+        game: &GameCode,
+        select: bool,
+        launches: &[(GameCode, &str)],
+        ids: &[String],
+        tickets: &mut [Option<Ticket>],
+        protocol: NProtocol<'_>,
+    ) -> Result<(), Error> {
+        if select {
+            self.characters(game).await?;
+        }
+        for (((g, _), id), ticket) in launches.iter().zip(ids).zip(tickets) {
+            if g == game {
+                let res = self.launch(id, protocol.clone()).await?;
+                *ticket = Some(Ticket::new(id, &res));
+            }
+        }
+        Ok(())
+    }
+}
@@ -1,23 +1,115 @@
+//! Types and parsers for the play.net eaccess (SGE) login protocol.
+//!
+//! The protocol types, parsers and [`hash_password`] only need `alloc`; build with
+//! `default-features = false` for `no_std` targets such as `wasm32-unknown-unknown`. The
+//! `Client` needs the `client` feature, which pulls in std and tokio.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+extern crate alloc;
+
+use alloc::{
+    format,
+    string::{String, ToString},
+    vec::Vec,
+};
+use core::fmt;
+
 use chumsky::{extra::ParserExtra, prelude::*};
 use itertools::{Itertools, chain};
 
+#[cfg(feature = "client")]
+mod client;
+#[cfg(feature = "client")]
+mod launch;
+#[cfg(feature = "nom")]
+pub mod nom;
+mod request;
+#[cfg(feature = "client")]
+mod retry;
+#[cfg(feature = "client")]
+mod roster;
+#[cfg(feature = "serde")]
+mod serialize;
+
+#[cfg(feature = "client")]
+pub use client::{Client, Config, Step};
+#[cfg(feature = "client")]
+pub use launch::Ticket;
+pub use request::Request;
+#[cfg(feature = "client")]
+pub use retry::{Retry, retry};
+#[cfg(feature = "client")]
+pub use roster::{Character, Failure, Instance, Roster};
+
 pub const ENDPOINT: (&str, u16) = ("eaccess.play.net", 7900);
 
+/// Stands in for a secret in `Debug` output, unless the `insecure-debug` feature is enabled.
+///
+/// Hash keys, session keys, launch keys and passwords are all wrapped in this before they get
+/// anywhere near a log. Note that `Display` on the messages is their wire form, and is never
+/// redacted.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub struct Redacted<T>(pub T);
+
+impl<T: fmt::Debug> fmt::Debug for Redacted<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if cfg!(feature = "insecure-debug") {
+            self.0.fmt(f)
+        } else {
+            f.write_str("<redacted>")
+        }
+    }
+}
+
 #[derive(thiserror::Error, Debug)]
 pub enum Error {
     #[error("{0}")]
     ParseError(String),
+    #[cfg(feature = "client")]
+    #[error("{source} during {step}")]
+    Io {
+        step: Step,
+        #[source]
+        source: std::io::Error,
+    },
+    #[cfg(feature = "client")]
+    #[error("eaccess closed the connection during {0}")]
+    Closed(Step),
+    #[cfg(feature = "client")]
+    #[error("timed out waiting for {0}")]
+    Timeout(Step),
+    #[cfg(feature = "client")]
+    #[error("login did not finish before the deadline")]
+    Deadline,
+    #[cfg(feature = "client")]
+    #[error("no character {character} on {game}")]
+    UnknownCharacter { game: GameCode, character: String },
+    /// [`Client::launch_all`] failed partway; `tickets` were issued before it did, and are
+    /// still good.
+    #[cfg(feature = "client")]
+    #[error("{source} after {} launches", .tickets.len())]
+    PartialLaunch {
+        tickets: Vec<Ticket>,
+        #[source]
+        source: Box<Error>,
+    },
 }
 
 /// Hashes a password using the hash key provided by play.net
+///
+/// The arithmetic wraps, so bytes outside printable ASCII hash (and unhash) without panicking.
 pub fn hash_password(
     password: impl Iterator<Item = u8>,
     hash_key: impl Iterator<Item = u8>,
 ) -> impl Iterator<Item = u8> {
-    password.zip(hash_key).map(|(p, h)| ((p - 0x20) ^ h) + 0x20)
+    password
+        .zip(hash_key)
+        .map(|(p, h)| (p.wrapping_sub(0x20) ^ h).wrapping_add(0x20))
 }
 
-#[derive(Debug, Clone)]
+#[derive(Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct K<'a> {
     pub key: &'a str,
 }
@@ -35,7 +127,22 @@ impl<'a> Message<'a> for K<'a> {
     }
 }
 
-#[derive(Debug, Clone)]
+impl fmt::Debug for K<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("K")
+            .field("key", &Redacted(self.key))
+            .finish()
+    }
+}
+
+impl fmt::Display for K<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "{}", self.key)
+    }
+}
+
+#[derive(Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct A<'a> {
     pub account: &'a str,
     pub key: &'a str,
@@ -62,7 +169,24 @@ impl<'a> Message<'a> for A<'a> {
     }
 }
 
-#[derive(Debug, Clone)]
+impl fmt::Debug for A<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("A")
+            .field("account", &self.account)
+            .field("key", &Redacted(self.key))
+            .field("name", &self.name)
+            .finish()
+    }
+}
+
+impl fmt::Display for A<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "A\t{}\tKEY\t{}\t{}", self.account, self.key, self.name)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct M<'a>(pub Vec<(&'a str, &'a str)>);
 
 impl M<'_> {
@@ -84,7 +208,14 @@ impl<'a> Message<'a> for M<'a> {
             .map(|v: Vec<(&'a str, &'a str)>| Self(v))
     }
 }
-#[derive(Debug, Clone)]
+
+impl fmt::Display for M<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "M\t{}", pairs(&self.0, "\t"))
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub enum NEnvironment<'a> {
     /// PRODUCTION
     Production,
@@ -93,6 +224,16 @@ pub enum NEnvironment<'a> {
     Other(&'a str),
 }
 
+impl<'a> From<NEnvironment<'a>> for &'a str {
+    fn from(value: NEnvironment<'a>) -> Self {
+        match value {
+            NEnvironment::Production => "PRODUCTION",
+            NEnvironment::Development => "DEVELOPMENT",
+            NEnvironment::Other(other) => other,
+        }
+    }
+}
+
 impl<'a> From<&'a str> for NEnvironment<'a> {
     fn from(value: &'a str) -> Self {
         match value {
@@ -103,10 +244,13 @@ impl<'a> From<&'a str> for NEnvironment<'a> {
     }
 }
 
-#[derive(Debug, Clone)]
+/// The protocol the game server will speak, as given in N and asked for with L.
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub enum NProtocol<'a> {
-    /// STORM
+    /// STORM: the XML stream that StormFront, Wrayth and most modern frontends read
     Storm,
+    /// PLAY: the plain GSL stream of the Wizard and other non-XML frontends
+    Play,
     Other(&'a str),
 }
 
@@ -114,6 +258,7 @@ impl<'a> From<NProtocol<'a>> for &'a str {
     fn from(value: NProtocol<'a>) -> Self {
         match value {
             NProtocol::Storm => "STORM",
+            NProtocol::Play => "PLAY",
             NProtocol::Other(other) => other,
         }
     }
@@ -123,12 +268,13 @@ impl<'a> From<&'a str> for NProtocol<'a> {
     fn from(value: &'a str) -> Self {
         match value {
             "STORM" => Self::Storm,
+            "PLAY" => Self::Play,
             other => Self::Other(other),
         }
     }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub enum NAccess<'a> {
     /// no value
     None,
@@ -137,6 +283,16 @@ pub enum NAccess<'a> {
     Other(&'a str),
 }
 
+impl<'a> From<NAccess<'a>> for Option<&'a str> {
+    fn from(value: NAccess<'a>) -> Self {
+        match value {
+            NAccess::None => None,
+            NAccess::Trial => Some("TRIAL"),
+            NAccess::Other(other) => Some(other),
+        }
+    }
+}
+
 impl<'a> From<Option<&'a str>> for NAccess<'a> {
     fn from(value: Option<&'a str>) -> Self {
         match value {
@@ -147,7 +303,76 @@ impl<'a> From<Option<&'a str>> for NAccess<'a> {
     }
 }
 
-#[derive(Debug, Clone)]
+/// A game (instance) code, as listed by M and taken by N, F, G and P.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum GameCode {
+    /// GS3, GemStone IV
+    GemStone,
+    /// GSX, GemStone IV Platinum
+    GemStonePlatinum,
+    /// GSF, GemStone IV Shattered
+    GemStoneShattered,
+    /// GST, GemStone IV Test
+    GemStoneTest,
+    /// DR, DragonRealms
+    DragonRealms,
+    /// DRX, DragonRealms Platinum
+    DragonRealmsPlatinum,
+    /// DRF, DragonRealms The Fallen
+    DragonRealmsFallen,
+    /// DRT, DragonRealms Test
+    DragonRealmsTest,
+    Other(String),
+}
+
+impl GameCode {
+    pub fn as_str(&self) -> &str {
+        match self {
+            Self::GemStone => "GS3",
+            Self::GemStonePlatinum => "GSX",
+            Self::GemStoneShattered => "GSF",
+            Self::GemStoneTest => "GST",
+            Self::DragonRealms => "DR",
+            Self::DragonRealmsPlatinum => "DRX",
+            Self::DragonRealmsFallen => "DRF",
+            Self::DragonRealmsTest => "DRT",
+            Self::Other(other) => other,
+        }
+    }
+}
+
+impl From<&str> for GameCode {
+    fn from(value: &str) -> Self {
+        match value {
+            "GS3" => Self::GemStone,
+            "GSX" => Self::GemStonePlatinum,
+            "GSF" => Self::GemStoneShattered,
+            "GST" => Self::GemStoneTest,
+            "DR" => Self::DragonRealms,
+            "DRX" => Self::DragonRealmsPlatinum,
+            "DRF" => Self::DragonRealmsFallen,
+            "DRT" => Self::DragonRealmsTest,
+            other => Self::Other(other.into()),
+        }
+    }
+}
+
+impl core::str::FromStr for GameCode {
+    type Err = core::convert::Infallible;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(s.into())
+    }
+}
+
+impl fmt::Display for GameCode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct N<'a> {
     pub environment: NEnvironment<'a>,
     pub protocol: NProtocol<'a>,
@@ -155,8 +380,8 @@ pub struct N<'a> {
 }
 
 impl N<'_> {
-    pub fn out(node: &str) -> String {
-        format!("N\t{node}\n")
+    pub fn out(game: &GameCode) -> String {
+        format!("N\t{game}\n")
     }
 }
 
@@ -175,7 +400,19 @@ impl<'a> Message<'a> for N<'a> {
     }
 }
 
-#[derive(Debug, Clone)]
+impl fmt::Display for N<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let environment: &str = self.environment.clone().into();
+        let protocol: &str = self.protocol.clone().into();
+        write!(f, "N\t{environment}|{protocol}")?;
+        if let Some(access) = Option::<&str>::from(self.access.clone()) {
+            write!(f, "|{access}")?;
+        }
+        writeln!(f)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub enum PaymentStatus<'a> {
     /// NEED_BILL
     NeedBill,
@@ -194,6 +431,19 @@ pub enum PaymentStatus<'a> {
     Other(&'a str),
 }
 
+impl<'a> From<PaymentStatus<'a>> for &'a str {
+    fn from(value: PaymentStatus<'a>) -> Self {
+        match value {
+            PaymentStatus::NeedBill => "NEED_BILL",
+            PaymentStatus::Free => "FREE",
+            PaymentStatus::FreeToPlay => "FREE_TO_PLAY",
+            PaymentStatus::Expired => "EXPIRED",
+            PaymentStatus::NewToGame => "NEW_TO_GAME",
+            PaymentStatus::Other(other) => other,
+        }
+    }
+}
+
 impl<'a> From<&'a str> for PaymentStatus<'a> {
     fn from(value: &'a str) -> Self {
         match value {
@@ -207,12 +457,13 @@ impl<'a> From<&'a str> for PaymentStatus<'a> {
     }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct F<'a>(pub PaymentStatus<'a>);
 
 impl F<'_> {
-    pub fn out(node: &str) -> String {
-        format!("F\t{node}\n")
+    pub fn out(game: &GameCode) -> String {
+        format!("F\t{game}\n")
     }
 }
 
@@ -224,10 +475,17 @@ impl<'a> Message<'a> for F<'a> {
     }
 }
 
+impl fmt::Display for F<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "F\t{}", <&str>::from(self.0.clone()))
+    }
+}
+
 /// This struct requests general info for an instance, and includes links.
 ///
 /// Send this struct before sending C (character request)
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct G<'a> {
     pub name: &'a str,
     pub model: PaymentStatus<'a>,
@@ -235,8 +493,8 @@ pub struct G<'a> {
 }
 
 impl G<'_> {
-    pub fn out(node: &str) -> String {
-        format!("G\t{node}\n")
+    pub fn out(game: &GameCode) -> String {
+        format!("G\t{game}\n")
     }
 }
 
@@ -258,8 +516,21 @@ impl<'a> Message<'a> for G<'a> {
     }
 }
 
+impl fmt::Display for G<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "G\t{}\t{}\t0\t\t{}",
+            self.name,
+            <&str>::from(self.model.clone()),
+            pairs(&self.data, "="),
+        )
+    }
+}
+
 /// I have no idea what this information means or what the message does.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct P<'a> {
     pub p0: &'a str,
     pub p1: &'a str,
@@ -270,8 +541,8 @@ pub struct P<'a> {
 }
 
 impl P<'_> {
-    pub fn out(node: &str) -> String {
-        format!("P\t{node}\n")
+    pub fn out(game: &GameCode) -> String {
+        format!("P\t{game}\n")
     }
 }
 
@@ -296,11 +567,22 @@ impl<'a> Message<'a> for P<'a> {
     }
 }
 
+impl fmt::Display for P<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "P\t{}\t{}\t{}\t{}\t{}\t{}",
+            self.p0, self.p1, self.p2, self.p3, self.p4, self.p5
+        )
+    }
+}
+
 /// Send/parse request for character list for a specific instance.
 ///
 /// Note that you cannot include an instance ID in the request; instead, you must send
 /// `G::out(<node>)` first, after which `C::out` will return the characters for the node.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct C<'a> {
     pub num_characters: u64,
     pub max_characters: u64,
@@ -340,8 +622,23 @@ impl<'a> Message<'a> for C<'a> {
     }
 }
 
+impl fmt::Display for C<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "C\t{}\t{}\t{}\t{}",
+            self.num_characters, self.max_characters, self.n0, self.n1
+        )?;
+        for (id, name) in &self.characters {
+            write!(f, "\t{id}\t{name}")?;
+        }
+        writeln!(f)
+    }
+}
+
 /// You likely want (`game_host`:`game_port`) and `key`
-#[derive(Debug, Clone)]
+#[derive(Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct L<'a> {
     /// UPPORT
     pub upport: u64,
@@ -365,6 +662,21 @@ impl L<'_> {
     pub fn out<'a>(character_id: &str, protocol: impl Into<&'a str>) -> String {
         format!("L\t{character_id}\t{}\n", Into::<&str>::into(protocol))
     }
+
+    /// The ticket in the `.sal` launch file format that SGE frontends are started with.
+    pub fn to_sal(&self) -> String {
+        format!(
+            "UPPORT={}\nGAME={}\nGAMECODE={}\nFULLGAMENAME={}\nGAMEFILE={}\nGAMEHOST={}\nGAMEPORT={}\nKEY={}\n",
+            self.upport,
+            self.game,
+            self.game_code,
+            self.full_game_name,
+            self.game_file,
+            self.game_host,
+            self.game_port,
+            self.key,
+        )
+    }
 }
 
 impl<'a> Message<'a> for L<'a> {
@@ -396,6 +708,38 @@ impl<'a> Message<'a> for L<'a> {
     }
 }
 
+impl fmt::Debug for L<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("L")
+            .field("upport", &self.upport)
+            .field("game", &self.game)
+            .field("game_code", &self.game_code)
+            .field("full_game_name", &self.full_game_name)
+            .field("game_file", &self.game_file)
+            .field("game_host", &self.game_host)
+            .field("game_port", &self.game_port)
+            .field("key", &Redacted(self.key))
+            .finish()
+    }
+}
+
+impl fmt::Display for L<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "L\tOK\tUPPORT={}\tGAME={}\tGAMECODE={}\tFULLGAMENAME={}\tGAMEFILE={}\tGAMEHOST={}\tGAMEPORT={}\tKEY={}",
+            self.upport,
+            self.game,
+            self.game_code,
+            self.full_game_name,
+            self.game_file,
+            self.game_host,
+            self.game_port,
+            self.key,
+        )
+    }
+}
+
 pub trait Message<'a>: Sized {
     fn parser() -> impl Parser<'a, &'a str, Self, extra::Err<Simple<'a, char>>>;
 
@@ -407,10 +751,20 @@ pub trait Message<'a>: Sized {
     }
 }
 
+/// Formats `[(a, b), ...]` as `a{sep}b\ta{sep}b...`, the way M and G lay out their lists.
+fn pairs<'s>(items: &'s [(&str, &str)], sep: &'s str) -> impl fmt::Display + 's {
+    items
+        .iter()
+        .format_with("\t", move |(a, b), f| f(&format_args!("{a}{sep}{b}")))
+}
+
 fn number<'a>() -> impl Parser<'a, &'a str, u64, extra::Err<Simple<'a, char>>> {
     one_of("0123456789")
         .repeated()
+        .at_least(1)
         .to_slice()
+        // rejects values that overflow a u64, so the unwrap below can't fail
+        .filter(|v: &&str| v.parse::<u64>().is_ok())
         .map(|v: &str| v.parse().unwrap())
 }
 
@@ -426,3 +780,21 @@ where
         .to_slice()
         .then_ignore(parser)
 }
+
+/// Entry points into the private parser helpers, for the fuzz targets.
+#[cfg(fuzzing)]
+#[doc(hidden)]
+pub mod fuzzing {
+    use super::*;
+
+    pub fn taken_ignore(inp: &str, delimiter: char) -> Option<(&str, &str)> {
+        super::taken_ignore::<_, extra::Err<Simple<char>>>(just(delimiter))
+            .then(any().repeated().to_slice())
+            .parse(inp)
+            .into_output()
+    }
+
+    pub fn number(inp: &str) -> Option<u64> {
+        super::number().then_ignore(end()).parse(inp).into_output()
+    }
+}
@@ -0,0 +1,91 @@
+use alloc::vec::Vec;
+use core::fmt;
+
+use crate::{A, C, F, G, GameCode, K, L, M, N, NProtocol, P, Redacted};
+
+/// A request to eaccess, for when the request needs to be passed around as a value rather than
+/// written straight away with one of the `out` functions.
+#[derive(Clone, PartialEq, Eq)]
+pub enum Request<'a> {
+    /// Ask for the password hash key.
+    K,
+    /// Log in, with the password already run through [`hash_password`](crate::hash_password).
+    A {
+        account: &'a str,
+        hashed_password: &'a [u8],
+    },
+    /// List the games on the account.
+    M,
+    N {
+        game: GameCode,
+    },
+    F {
+        game: GameCode,
+    },
+    G {
+        game: GameCode,
+    },
+    P {
+        game: GameCode,
+    },
+    /// List the characters on the game last selected with G.
+    C,
+    /// Launch a character.
+    L {
+        character: &'a str,
+        protocol: NProtocol<'a>,
+    },
+}
+
+impl Request<'_> {
+    /// The request as it goes on the wire, trailing newline included.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        match self {
+            Self::K => K::out().into(),
+            Self::A {
+                account,
+                hashed_password,
+            } => A::out(account.bytes(), hashed_password.iter().copied()),
+            Self::M => M::out().into(),
+            Self::N { game } => N::out(game).into_bytes(),
+            Self::F { game } => F::out(game).into_bytes(),
+            Self::G { game } => G::out(game).into_bytes(),
+            Self::P { game } => P::out(game).into_bytes(),
+            Self::C => C::out().into(),
+            Self::L {
+                character,
+                protocol,
+            } => L::out(character, protocol.clone()).into_bytes(),
+        }
+    }
+}
+
+impl fmt::Debug for Request<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::K => f.write_str("K"),
+            Self::A {
+                account,
+                hashed_password,
+            } => f
+                .debug_struct("A")
+                .field("account", account)
+                .field("hashed_password", &Redacted(hashed_password))
+                .finish(),
+            Self::M => f.write_str("M"),
+            Self::N { game } => f.debug_struct("N").field("game", game).finish(),
+            Self::F { game } => f.debug_struct("F").field("game", game).finish(),
+            Self::G { game } => f.debug_struct("G").field("game", game).finish(),
+            Self::P { game } => f.debug_struct("P").field("game", game).finish(),
+            Self::C => f.write_str("C"),
+            Self::L {
+                character,
+                protocol,
+            } => f
+                .debug_struct("L")
+                .field("character", character)
+                .field("protocol", protocol)
+                .finish(),
+        }
+    }
+}
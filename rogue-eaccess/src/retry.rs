@@ -0,0 +1,87 @@
+use std::{
+    collections::hash_map::RandomState,
+    hash::{BuildHasher, Hasher},
+    io::ErrorKind,
+    time::Duration,
+};
+
+use crate::Error;
+
+/// Exponential backoff with full jitter.
+#[derive(Debug, Clone)]
+pub struct Retry {
+    /// total tries, including the first; 1 disables retrying
+    pub attempts: u32,
+    /// the upper bound of the first delay; doubles after every failure
+    pub initial: Duration,
+    /// the largest the upper bound may grow to
+    pub max: Duration,
+}
+
+impl Default for Retry {
+    fn default() -> Self {
+        Self {
+            attempts: 4,
+            initial: Duration::from_millis(250),
+            max: Duration::from_secs(5),
+        }
+    }
+}
+
+impl Retry {
+    /// A random delay in `0..=min(max, initial * 2^attempt)`.
+    pub fn delay(&self, attempt: u32) -> Duration {
+        let cap = self
+            .initial
+            .saturating_mul(2u32.saturating_pow(attempt))
+            .min(self.max);
+        let random = RandomState::new().build_hasher().finish();
+        cap.mul_f64(random as f64 / u64::MAX as f64)
+    }
+}
+
+impl Error {
+    /// Whether the error is worth retrying: a dropped connection or a timeout, and never one
+    /// that happened during a step that isn't safe to repeat.
+    pub fn is_transient(&self) -> bool {
+        match self {
+            Self::Timeout(step) | Self::Closed(step) => step.is_idempotent(),
+            Self::Io { step, source } => {
+                step.is_idempotent()
+                    && matches!(
+                        source.kind(),
+                        ErrorKind::ConnectionRefused
+                            | ErrorKind::ConnectionReset
+                            | ErrorKind::ConnectionAborted
+                            | ErrorKind::BrokenPipe
+                            | ErrorKind::UnexpectedEof
+                            | ErrorKind::TimedOut
+                    )
+            }
+            _ => false,
+        }
+    }
+}
+
+/// Runs `op` until it succeeds, fails with a non-transient error, or runs out of attempts.
+///
+/// `op` has to start from scratch each time (usually with a fresh connection), since the SGE
+/// session doesn't survive a dropped connection. Don't put L inside it.
+pub async fn retry<T, F, Fut>(retry: &Retry, mut op: F) -> Result<T, Error>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = Result<T, Error>>,
+{
+    let mut attempt = 0;
+    loop {
+        match op().await {
+            Err(e) if e.is_transient() && attempt + 1 < retry.attempts => {
+                let delay = retry.delay(attempt);
+                tracing::debug!(attempt, error = %e, ?delay, "retrying");
+                tokio::time::sleep(delay).await;
+                attempt += 1;
+            }
+            res => return res,
+        }
+    }
+}
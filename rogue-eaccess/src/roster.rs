@@ -0,0 +1,98 @@
+use tokio::io::{AsyncRead, AsyncWrite};
+
+use crate::{C, Client, Error, G, GameCode, PaymentStatus};
+
+/// Every character on every game an account can see.
+#[derive(Debug)]
+pub struct Roster {
+    pub games: Vec<Instance>,
+    /// games whose G or C failed, in the order they were walked
+    pub failed: Vec<Failure>,
+}
+
+/// A game that couldn't be looked at.
+#[derive(Debug)]
+pub struct Failure {
+    /// game code, e.g. GS3
+    pub code: String,
+    pub name: String,
+    pub error: Error,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Instance {
+    /// game code, e.g. GS3
+    pub code: String,
+    pub name: String,
+    /// payment status, as sent in the G response
+    pub status: String,
+    pub num_characters: u64,
+    pub max_characters: u64,
+    pub characters: Vec<Character>,
+}
+
+impl Instance {
+    pub fn status(&self) -> PaymentStatus<'_> {
+        self.status.as_str().into()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Character {
+    pub id: String,
+    pub name: String,
+}
+
+impl<S: AsyncRead + AsyncWrite + Unpin> Client<S> {
+    /// Walks every game in M, issuing G and C for each.
+    ///
+    /// A game the server won't show is recorded in [`Roster::failed`] and the walk carries on;
+    /// only losing the connection (a [transient](Error::is_transient) error) ends it early.
+    /// Nothing is launched; no L request is ever sent.
+    pub async fn roster(&mut self) -> Result<Roster, Error> {
+        let games: Vec<(String, String)> = self
+            .games()
+            .await?
+            .0
+            .into_iter()
+            .map(|(code, name)| (code.to_owned(), name.to_owned()))
+            .collect();
+
+        let mut roster = Roster {
+            games: Vec::new(),
+            failed: Vec::new(),
+        };
+        for (code, name) in games {
+            match self.instance(&code, &name).await {
+                Ok(instance) => roster.games.push(instance),
+                Err(error) if error.is_transient() => return Err(error),
+                Err(error) => roster.failed.push(Failure { code, name, error }),
+            }
+        }
+
+        Ok(roster)
+    }
+
+    /// Issues G and C for one game.
+    async fn instance(&mut self, code: &str, name: &str) -> Result<Instance, Error> {
+        let game = GameCode::from(code);
+        let status = <&str>::from(self.request::<G>(G::out(&game)).await?.model).to_owned();
+        let res = self.request::<C>(C::out()).await?;
+
+        Ok(Instance {
+            code: code.to_owned(),
+            name: name.to_owned(),
+            status,
+            num_characters: res.num_characters,
+            max_characters: res.max_characters,
+            characters: res
+                .characters
+                .into_iter()
+                .map(|(id, name)| Character {
+                    id: id.to_owned(),
+                    name: name.to_owned(),
+                })
+                .collect(),
+        })
+    }
+}
@@ -0,0 +1,29 @@
+//! `Serialize` for the string-like enums, as the strings they stand for on the wire.
+
+use serde::{Serialize, Serializer};
+
+use crate::{NAccess, NEnvironment, NProtocol, PaymentStatus};
+
+impl Serialize for NEnvironment<'_> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.clone().into())
+    }
+}
+
+impl Serialize for NProtocol<'_> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.clone().into())
+    }
+}
+
+impl Serialize for NAccess<'_> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        Option::<&str>::from(self.clone()).serialize(serializer)
+    }
+}
+
+impl Serialize for PaymentStatus<'_> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.clone().into())
+    }
+}
@@ -0,0 +1,88 @@
+//! The chumsky and nom backends must agree on every well-formed message, and on which lines
+//! aren't one.
+
+mod common;
+
+use common::*;
+use proptest::prelude::*;
+use rogue_eaccess::{A, C, F, G, K, L, M, N, P, nom};
+
+macro_rules! backends_agree {
+    ($($name:ident: $ty:ident = $strategy:expr;)*) => {
+        proptest! {
+            $(
+                #[test]
+                fn $name(msg in $strategy) {
+                    let line = msg.to_string();
+                    let chumsky = <$ty as rogue_eaccess::Message>::parse(&line).unwrap();
+                    let nom = <$ty as nom::Message>::parse(&line).unwrap();
+                    prop_assert_eq!(chumsky, nom);
+                }
+            )*
+        }
+    };
+}
+
+backends_agree! {
+    k_agrees: K = k();
+    a_agrees: A = a();
+    m_agrees: M = m();
+    n_agrees: N = n();
+    f_agrees: F = f();
+    g_agrees: G = g();
+    p_agrees: P = p();
+    c_agrees: C = c();
+    l_agrees: L = l();
+}
+
+/// Parses `line` with both backends, which must agree on whether it parses and what it holds.
+fn agree<'a, T>(line: &'a str) -> Result<(), TestCaseError>
+where
+    T: rogue_eaccess::Message<'a> + nom::Message<'a> + PartialEq + std::fmt::Debug,
+{
+    let chumsky = <T as rogue_eaccess::Message>::parse(line).ok();
+    let nom = <T as nom::Message>::parse(line).ok();
+    prop_assert_eq!(chumsky, nom, "{:?}", line);
+    Ok(())
+}
+
+proptest! {
+    // lines made mostly of delimiters, so empty fields and misplaced separators come up often
+    #[test]
+    fn backends_agree_on_near_misses(
+        prefix in prop_oneof![
+            Just(""), Just("A\t"), Just("M\t"), Just("N\t"), Just("F\t"), Just("G\t"),
+            Just("P\t"), Just("C\t"), Just("L\tOK\tUPPORT="),
+        ],
+        rest in "[\t\n|=0a]{0,16}",
+    ) {
+        let line = format!("{prefix}{rest}");
+        agree::<K>(&line)?;
+        agree::<A>(&line)?;
+        agree::<M>(&line)?;
+        agree::<N>(&line)?;
+        agree::<F>(&line)?;
+        agree::<G>(&line)?;
+        agree::<P>(&line)?;
+        agree::<C>(&line)?;
+        agree::<L>(&line)?;
+    }
+}
+
+#[test]
+fn empty_fields_parse_the_same() {
+    for line in [
+        "\n",
+        "A\t\tKEY\t\t\n",
+        "M\t\t\n",
+        "P\t\t\t\t\t\t\n",
+        "C\t1\t2\t3\t4\t\t\n",
+    ] {
+        agree::<K>(line).unwrap();
+        agree::<A>(line).unwrap();
+        agree::<M>(line).unwrap();
+        agree::<P>(line).unwrap();
+        agree::<C>(line).unwrap();
+    }
+    assert!(<P as nom::Message>::parse("P\t\t\t\t\t\t\n").is_ok());
+}
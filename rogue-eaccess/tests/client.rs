@@ -0,0 +1,307 @@
+use std::{
+    sync::atomic::{AtomicU32, Ordering},
+    time::Duration,
+};
+
+use rogue_eaccess::{Client, Config, Error, GameCode, NProtocol, Retry, Step, retry};
+use tokio::{
+    io::{AsyncBufReadExt, AsyncWriteExt, BufReader},
+    net::TcpListener,
+};
+
+#[tokio::test(start_paused = true)]
+async fn stalled_steps_time_out_by_name() {
+    let (stream, _server) = tokio::io::duplex(1024);
+    let mut client = Client::with_config(
+        stream,
+        Config {
+            step_timeout: Duration::from_secs(1),
+            ..Config::default()
+        },
+    );
+
+    assert!(matches!(
+        client.games().await,
+        Err(Error::Timeout(Step::Games))
+    ));
+}
+
+#[tokio::test]
+async fn launch_is_never_retried() {
+    let attempts = AtomicU32::new(0);
+    let res: Result<(), _> = retry(&Retry::default(), || async {
+        attempts.fetch_add(1, Ordering::Relaxed);
+        Err(Error::Timeout(Step::Launch))
+    })
+    .await;
+
+    assert!(res.is_err());
+    assert_eq!(attempts.load(Ordering::Relaxed), 1);
+}
+
+#[tokio::test]
+async fn launch_is_not_retried_when_the_connection_drops() {
+    let attempts = AtomicU32::new(0);
+    let res = retry(&Retry::default(), || async {
+        attempts.fetch_add(1, Ordering::Relaxed);
+        let (stream, server) = tokio::io::duplex(1024);
+        // read the L, then hang up without answering it
+        tokio::spawn(async move {
+            let mut server = BufReader::new(server);
+            server.read_line(&mut String::new()).await.unwrap();
+        });
+
+        let mut client = Client::new(stream);
+        client
+            .launch("W_GS3_000", NProtocol::Storm)
+            .await
+            .map(|_| ())
+    })
+    .await;
+
+    assert!(matches!(res, Err(Error::Closed(Step::Launch))));
+    assert_eq!(attempts.load(Ordering::Relaxed), 1);
+}
+
+#[tokio::test]
+async fn login_survives_a_dropped_connection() {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+
+    tokio::spawn(async move {
+        drop(listener.accept().await.unwrap());
+
+        let (stream, _) = listener.accept().await.unwrap();
+        let mut stream = BufReader::new(stream);
+        let mut line = String::new();
+        for response in ["KEYKEYKEY\n", "A\tACCOUNT\tKEY\tabcdef\tSome Person\n"] {
+            stream.read_line(&mut line).await.unwrap();
+            stream.write_all(response.as_bytes()).await.unwrap();
+        }
+        // keep the connection open until the client is done with it
+        stream.read_line(&mut line).await.unwrap();
+    });
+
+    let client = Client::connect_and_login(
+        addr,
+        "ACCOUNT",
+        "password",
+        Config {
+            retry: Retry {
+                attempts: 3,
+                initial: Duration::from_millis(10),
+                max: Duration::from_millis(10),
+            },
+            ..Config::default()
+        },
+    )
+    .await;
+
+    assert!(client.is_ok());
+}
+
+/// Answers M, G, C and L for two games, numbering the launch keys, and records every request.
+/// M also lists a third game, GSX, which G refuses, and L refuses DR's second character.
+async fn two_games(stream: tokio::io::DuplexStream) -> Vec<String> {
+    let mut stream = BufReader::new(stream);
+    let mut requests = Vec::new();
+    let mut line = String::new();
+    let mut selected = String::new();
+    while stream.read_line(&mut line).await.unwrap() > 0 {
+        let response = match line.split('\t').next().unwrap() {
+            "M\n" => {
+                "M\tGS3\tGemStone IV\tGSX\tGemStone IV Platinum\tDR\tDragonRealms\n".to_owned()
+            }
+            "G" => {
+                selected = line.trim_end()[2..].to_owned();
+                if selected == "GSX" {
+                    "X\n".to_owned()
+                } else {
+                    "G\tSome Game\tFREE_TO_PLAY\t0\t\tROOT=sgc\n".to_owned()
+                }
+            }
+            "C\n" if selected == "GS3" => {
+                "C\t2\t5\t1\t1\tW_GS3_000\tFoo\tW_GS3_001\tBar\n".to_owned()
+            }
+            "C\n" => "C\t2\t5\t1\t1\tW_DR_000\tBaz\tW_DR_001\tQux\n".to_owned(),
+            "L" if line.starts_with("L\tW_DR_001\t") => "L\tPROBLEM\n".to_owned(),
+            "L" => format!(
+                "L\tOK\tUPPORT=5535\tGAME=STORM\tGAMECODE={selected}\tFULLGAMENAME=Wrayth\tGAMEFILE=WRAYTH.EXE\tGAMEHOST=localhost\tGAMEPORT=10024\tKEY=key{}\n",
+                requests
+                    .iter()
+                    .filter(|r: &&String| r.starts_with('L'))
+                    .count()
+            ),
+            _ => "X\n".to_owned(),
+        };
+        requests.push(std::mem::take(&mut line));
+        stream
+            .get_mut()
+            .write_all(response.as_bytes())
+            .await
+            .unwrap();
+    }
+    requests
+}
+
+#[tokio::test]
+async fn launch_all_launches_across_games() {
+    let (stream, server) = tokio::io::duplex(1024);
+    let server = tokio::spawn(two_games(server));
+
+    let mut client = Client::new(stream);
+    let tickets = client
+        .launch_all(
+            &[
+                (GameCode::GemStone, "foo"),
+                (GameCode::DragonRealms, "W_DR_000"),
+                (GameCode::GemStone, "Bar"),
+            ],
+            NProtocol::Storm,
+        )
+        .await
+        .unwrap();
+    drop(client);
+
+    let tickets: Vec<_> = tickets
+        .iter()
+        .map(|t| (t.character.as_str(), t.game_code.as_str(), t.key.as_str()))
+        .collect();
+    assert_eq!(
+        tickets,
+        [
+            ("W_GS3_000", "GS3", "key1"),
+            ("W_DR_000", "DR", "key0"),
+            ("W_GS3_001", "GS3", "key2"),
+        ]
+    );
+    // DR is still selected after the lookups, so it launches first, and GS3 is selected once
+    assert_eq!(
+        server.await.unwrap(),
+        [
+            "G\tGS3\n",
+            "C\n",
+            "G\tDR\n",
+            "C\n",
+            "L\tW_DR_000\tSTORM\n",
+            "G\tGS3\n",
+            "C\n",
+            "L\tW_GS3_000\tSTORM\n",
+            "L\tW_GS3_001\tSTORM\n",
+        ]
+    );
+}
+
+#[tokio::test]
+async fn launch_all_checks_every_character_first() {
+    let (stream, server) = tokio::io::duplex(1024);
+    let server = tokio::spawn(two_games(server));
+
+    let mut client = Client::new(stream);
+    let res = client
+        .launch_all(
+            &[
+                (GameCode::GemStone, "Foo"),
+                (GameCode::DragonRealms, "Nobody"),
+            ],
+            NProtocol::Storm,
+        )
+        .await;
+    drop(client);
+
+    assert!(matches!(res, Err(Error::UnknownCharacter { .. })));
+    assert!(!server.await.unwrap().iter().any(|r| r.starts_with('L')));
+}
+
+#[tokio::test]
+async fn launch_all_keeps_the_tickets_issued_before_a_failure() {
+    let (stream, server) = tokio::io::duplex(1024);
+    let server = tokio::spawn(two_games(server));
+
+    let mut client = Client::new(stream);
+    let res = client
+        .launch_all(
+            &[
+                (GameCode::DragonRealms, "Qux"),
+                (GameCode::GemStone, "Foo"),
+                (GameCode::GemStone, "Bar"),
+            ],
+            NProtocol::Storm,
+        )
+        .await;
+    drop(client);
+
+    let Err(Error::PartialLaunch { tickets, source }) = res else {
+        panic!("expected a partial launch, got {res:?}");
+    };
+    let tickets: Vec<_> = tickets
+        .iter()
+        .map(|t| (t.character.as_str(), t.key.as_str()))
+        .collect();
+    assert_eq!(tickets, [("W_GS3_000", "key0"), ("W_GS3_001", "key1")]);
+    assert!(matches!(*source, Error::ParseError(_)));
+    // nothing is launched after the failure
+    let requests = server.await.unwrap();
+    assert_eq!(requests.last().unwrap(), "L\tW_DR_001\tSTORM\n");
+}
+
+#[tokio::test]
+async fn roster_walks_every_game_without_launching() {
+    let (stream, server) = tokio::io::duplex(1024);
+    let server = tokio::spawn(two_games(server));
+
+    let mut client = Client::new(stream);
+    let roster = client.roster().await.unwrap();
+    drop(client);
+
+    let games: Vec<_> = roster
+        .games
+        .iter()
+        .map(|game| {
+            let characters: Vec<_> = game
+                .characters
+                .iter()
+                .map(|c| (c.id.as_str(), c.name.as_str()))
+                .collect();
+            (
+                game.code.as_str(),
+                game.name.as_str(),
+                game.status.as_str(),
+                game.num_characters,
+                game.max_characters,
+                characters,
+            )
+        })
+        .collect();
+    assert_eq!(
+        games,
+        [
+            (
+                "GS3",
+                "GemStone IV",
+                "FREE_TO_PLAY",
+                2,
+                5,
+                vec![("W_GS3_000", "Foo"), ("W_GS3_001", "Bar")]
+            ),
+            (
+                "DR",
+                "DragonRealms",
+                "FREE_TO_PLAY",
+                2,
+                5,
+                vec![("W_DR_000", "Baz"), ("W_DR_001", "Qux")]
+            ),
+        ]
+    );
+
+    // the game G refused is recorded, and didn't stop the walk
+    assert_eq!(roster.failed.len(), 1);
+    assert_eq!(roster.failed[0].code, "GSX");
+    assert!(matches!(roster.failed[0].error, Error::ParseError(_)));
+
+    assert_eq!(
+        server.await.unwrap(),
+        ["M\n", "G\tGS3\n", "C\n", "G\tGSX\n", "G\tDR\n", "C\n"]
+    );
+}
@@ -0,0 +1,123 @@
+//! proptest generators for every eaccess message.
+//!
+//! Generated strings are leaked so the messages can borrow them for `'static`, the same way they
+//! would borrow a line read off the socket.
+
+#![allow(dead_code)]
+
+use proptest::{collection::vec, option, prelude::*};
+use rogue_eaccess::{A, C, F, G, K, L, M, N, NAccess, NEnvironment, NProtocol, P, PaymentStatus};
+
+/// A single field: never empty, and free of every delimiter the protocol uses.
+pub fn field() -> impl Strategy<Value = &'static str> {
+    "[^\t\n|=]{1,16}".prop_map(|s| &*s.leak())
+}
+
+/// Either one of `known` or an arbitrary field, so enums cover both named and `Other` variants.
+fn known_or_field(known: &'static [&'static str]) -> impl Strategy<Value = &'static str> {
+    prop_oneof![proptest::sample::select(known), field()]
+}
+
+pub fn environment() -> impl Strategy<Value = NEnvironment<'static>> {
+    known_or_field(&["PRODUCTION", "DEVELOPMENT"]).prop_map(NEnvironment::from)
+}
+
+pub fn protocol() -> impl Strategy<Value = NProtocol<'static>> {
+    known_or_field(&["STORM", "PLAY"]).prop_map(NProtocol::from)
+}
+
+pub fn access() -> impl Strategy<Value = NAccess<'static>> {
+    option::of(known_or_field(&["TRIAL"])).prop_map(NAccess::from)
+}
+
+pub fn payment_status() -> impl Strategy<Value = PaymentStatus<'static>> {
+    known_or_field(&[
+        "NEED_BILL",
+        "FREE",
+        "FREE_TO_PLAY",
+        "EXPIRED",
+        "NEW_TO_GAME",
+    ])
+    .prop_map(PaymentStatus::from)
+}
+
+pub fn k() -> impl Strategy<Value = K<'static>> {
+    field().prop_map(|key| K { key })
+}
+
+pub fn a() -> impl Strategy<Value = A<'static>> {
+    (field(), field(), field()).prop_map(|(account, key, name)| A { account, key, name })
+}
+
+pub fn m() -> impl Strategy<Value = M<'static>> {
+    vec((field(), field()), 1..64).prop_map(M)
+}
+
+pub fn n() -> impl Strategy<Value = N<'static>> {
+    (environment(), protocol(), access()).prop_map(|(environment, protocol, access)| N {
+        environment,
+        protocol,
+        access,
+    })
+}
+
+pub fn f() -> impl Strategy<Value = F<'static>> {
+    payment_status().prop_map(F)
+}
+
+pub fn g() -> impl Strategy<Value = G<'static>> {
+    (field(), payment_status(), vec((field(), field()), 1..16)).prop_map(|(name, model, data)| G {
+        name,
+        model,
+        data,
+    })
+}
+
+pub fn p() -> impl Strategy<Value = P<'static>> {
+    (field(), field(), field(), field(), field(), field()).prop_map(|(p0, p1, p2, p3, p4, p5)| P {
+        p0,
+        p1,
+        p2,
+        p3,
+        p4,
+        p5,
+    })
+}
+
+pub fn c() -> impl Strategy<Value = C<'static>> {
+    (
+        any::<u64>(),
+        any::<u64>(),
+        any::<u64>(),
+        any::<u64>(),
+        vec((field(), field()), 0..32),
+    )
+        .prop_map(|(num_characters, max_characters, n0, n1, characters)| C {
+            num_characters,
+            max_characters,
+            n0,
+            n1,
+            characters,
+        })
+}
+
+pub fn l() -> impl Strategy<Value = L<'static>> {
+    (
+        any::<u64>(),
+        (field(), field(), field(), field(), field()),
+        any::<u64>(),
+        field(),
+    )
+        .prop_map(
+            |(upport, (game, game_code, full_game_name, game_file, game_host), game_port, key)| L {
+                upport,
+                game,
+                game_code,
+                full_game_name,
+                game_file,
+                game_host,
+                game_port,
+                key,
+            },
+        )
+}
@@ -0,0 +1,135 @@
+mod common;
+
+use common::*;
+use proptest::prelude::*;
+use rogue_eaccess::{
+    A, C, F, G, GameCode, K, L, M, Message, N, NProtocol, P, Request, hash_password,
+};
+
+macro_rules! round_trip {
+    ($($name:ident: $ty:ident = $strategy:expr;)*) => {
+        proptest! {
+            $(
+                #[test]
+                fn $name(msg in $strategy) {
+                    let line = msg.to_string();
+                    prop_assert_eq!($ty::parse(&line).unwrap(), msg);
+                }
+            )*
+        }
+    };
+}
+
+round_trip! {
+    k_round_trips: K = k();
+    a_round_trips: A = a();
+    m_round_trips: M = m();
+    n_round_trips: N = n();
+    f_round_trips: F = f();
+    g_round_trips: G = g();
+    p_round_trips: P = p();
+    c_round_trips: C = c();
+    l_round_trips: L = l();
+}
+
+#[test]
+fn missing_numbers_are_errors() {
+    assert!(C::parse("C\t\t5\t0\t0\n").is_err());
+    assert!(L::parse("L\tOK\tUPPORT=\tGAME=STORM\tGAMECODE=GS3\tFULLGAMENAME=Wrayth\tGAMEFILE=WRAYTH.EXE\tGAMEHOST=storm.gs4.game.play.net\tGAMEPORT=10024\tKEY=abc\n").is_err());
+}
+
+#[test]
+fn overflowing_numbers_are_errors() {
+    assert!(C::parse("C\t99999999999999999999\t5\t0\t0\n").is_err());
+}
+
+proptest! {
+    #[test]
+    fn garbage_never_panics(line in "\\PC*") {
+        let _ = K::parse(&line);
+        let _ = A::parse(&line);
+        let _ = M::parse(&line);
+        let _ = N::parse(&line);
+        let _ = F::parse(&line);
+        let _ = G::parse(&line);
+        let _ = P::parse(&line);
+        let _ = C::parse(&line);
+        let _ = L::parse(&line);
+    }
+
+    #[test]
+    fn hashing_any_bytes_twice_gives_them_back(
+        password in proptest::collection::vec(any::<u8>(), 0..32),
+        key in proptest::collection::vec(any::<u8>(), 32),
+    ) {
+        let hashed: Vec<u8> = hash_password(password.iter().copied(), key.iter().copied()).collect();
+        let unhashed: Vec<u8> = hash_password(hashed.into_iter(), key.iter().copied()).collect();
+        prop_assert_eq!(unhashed, password);
+    }
+}
+
+#[test]
+fn hashes_control_characters() {
+    let hashed: Vec<u8> =
+        hash_password(b"\x01\tpw".iter().copied(), b"\xff\x00AB".iter().copied()).collect();
+    assert_eq!(hashed, b"\x3e\x09\x31\x35");
+}
+
+#[cfg(not(feature = "insecure-debug"))]
+#[test]
+fn keys_are_redacted_from_debug() {
+    let line = "L\tOK\tUPPORT=5535\tGAME=STORM\tGAMECODE=GS3\tFULLGAMENAME=Wrayth\tGAMEFILE=WRAYTH.EXE\tGAMEHOST=storm.gs4.game.play.net\tGAMEPORT=10024\tKEY=4e2a7ad3f1c90e6b\n";
+    assert!(!format!("{:?}", L::parse(line).unwrap()).contains("4e2a7ad3f1c90e6b"));
+    assert!(!format!("{:?}", K::parse("IKJPXQOEHDGHMPBI\n").unwrap()).contains("IKJPXQOEHDGHMPBI"));
+    assert!(
+        !format!(
+            "{:?}",
+            A::parse("A\tACCOUNT\tKEY\t4e2a7ad3\tSome Person\n").unwrap()
+        )
+        .contains("4e2a7ad3")
+    );
+}
+
+#[test]
+fn requests_match_out() {
+    let request = |r: Request| String::from_utf8(r.to_bytes()).unwrap();
+    assert_eq!(request(Request::K), K::out());
+    assert_eq!(
+        request(Request::A {
+            account: "ACCOUNT",
+            hashed_password: b"hashed",
+        }),
+        "A\tACCOUNT\thashed\n"
+    );
+    assert_eq!(request(Request::M), M::out());
+    assert_eq!(
+        request(Request::G {
+            game: GameCode::GemStone
+        }),
+        G::out(&GameCode::GemStone)
+    );
+    assert_eq!(
+        request(Request::N {
+            game: GameCode::from("DRX")
+        }),
+        "N\tDRX\n"
+    );
+    assert_eq!(
+        request(Request::F {
+            game: GameCode::from("GSZ")
+        }),
+        "F\tGSZ\n"
+    );
+    assert_eq!(request(Request::C), C::out());
+    assert_eq!(
+        request(Request::L {
+            character: "W_ACCOUNT_000",
+            protocol: NProtocol::Storm,
+        }),
+        L::out("W_ACCOUNT_000", NProtocol::Storm)
+    );
+    assert_eq!(
+        L::out("W_ACCOUNT_000", NProtocol::Play),
+        "L\tW_ACCOUNT_000\tPLAY\n"
+    );
+}
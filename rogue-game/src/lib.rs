@@ -0,0 +1,254 @@
+//! The connection to the game server itself, made with the key from an eaccess launch ticket.
+//!
+//! The game server greets with a banner line, then expects the key and a line saying which
+//! frontend is connecting and how it wants the stream. It answers with a blank line or two before
+//! the game proper starts.
+
+use std::{fmt, time::Duration};
+
+use eaccess::L;
+use tokio::{
+    io::{AsyncBufReadExt, AsyncRead, AsyncWrite, AsyncWriteExt, BufReader},
+    net::{TcpStream, ToSocketAddrs},
+    time::timeout,
+};
+
+#[derive(thiserror::Error, Debug)]
+pub enum Error {
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+    /// The game server hangs up on keys it doesn't like, so this usually means a bad, expired or
+    /// already used key.
+    #[error("the game server closed the connection during {0}")]
+    Closed(Step),
+    #[error("timed out waiting for {0}")]
+    Timeout(Step),
+    #[error("unexpected reply from the game server: {0:?}")]
+    UnexpectedReply(String),
+    #[error("game port {0} is out of range")]
+    BadPort(u64),
+}
+
+/// A step of the handshake, for errors.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Step {
+    Connect,
+    Banner,
+    Replies,
+}
+
+impl fmt::Display for Step {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Connect => "connect",
+            Self::Banner => "the banner",
+            Self::Replies => "the handshake replies",
+        })
+    }
+}
+
+/// The frontend to introduce ourselves as, in `/FE:`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Frontend {
+    /// WRAYTH
+    Wrayth,
+    /// STORMFRONT
+    StormFront,
+    /// WIZARD
+    Wizard,
+    Other(String),
+}
+
+impl Frontend {
+    pub fn as_str(&self) -> &str {
+        match self {
+            Self::Wrayth => "WRAYTH",
+            Self::StormFront => "STORMFRONT",
+            Self::Wizard => "WIZARD",
+            Self::Other(other) => other,
+        }
+    }
+}
+
+/// The platform to claim, in `/P:`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Platform {
+    /// WIN_UNKNOWN
+    Windows,
+    /// MAC
+    Mac,
+    Other(String),
+}
+
+impl Platform {
+    pub fn as_str(&self) -> &str {
+        match self {
+            Self::Windows => "WIN_UNKNOWN",
+            Self::Mac => "MAC",
+            Self::Other(other) => other,
+        }
+    }
+}
+
+/// How to introduce ourselves to the game server. The default is Wrayth, in XML mode.
+#[derive(Debug, Clone)]
+pub struct Handshake {
+    pub frontend: Frontend,
+    /// frontend version, in `/VERSION:`
+    pub version: String,
+    pub platform: Platform,
+    /// Ask for the XML stream. Needs a ticket launched with the STORM protocol.
+    pub xml: bool,
+    /// how long to wait for the connection, the banner, and the game stream to start
+    pub timeout: Duration,
+}
+
+impl Default for Handshake {
+    fn default() -> Self {
+        Self {
+            frontend: Frontend::Wrayth,
+            version: "1.0.1.28".to_owned(),
+            platform: Platform::Windows,
+            xml: true,
+            timeout: Duration::from_secs(15),
+        }
+    }
+}
+
+impl Handshake {
+    /// The line sent after the key, without the newline.
+    pub fn frontend_line(&self) -> String {
+        format!(
+            "/FE:{} /VERSION:{} /P:{}{}",
+            self.frontend.as_str(),
+            self.version,
+            self.platform.as_str(),
+            if self.xml { " /XML" } else { "" },
+        )
+    }
+}
+
+/// A game connection that has made it through the handshake.
+pub struct Connection<S = TcpStream> {
+    stream: BufReader<S>,
+    banner: String,
+}
+
+impl<S> fmt::Debug for Connection<S> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Connection")
+            .field("banner", &self.banner)
+            .finish_non_exhaustive()
+    }
+}
+
+impl Connection {
+    /// Connects to the game server named in a launch ticket.
+    pub async fn launch(ticket: &L<'_>, handshake: &Handshake) -> Result<Self, Error> {
+        let port = u16::try_from(ticket.game_port).map_err(|_| Error::BadPort(ticket.game_port))?;
+        Self::connect((ticket.game_host, port), ticket.key, handshake).await
+    }
+
+    pub async fn connect(
+        addr: impl ToSocketAddrs,
+        key: &str,
+        handshake: &Handshake,
+    ) -> Result<Self, Error> {
+        let stream = timeout(handshake.timeout, TcpStream::connect(addr))
+            .await
+            .map_err(|_| Error::Timeout(Step::Connect))??;
+        Self::handshake(stream, key, handshake).await
+    }
+}
+
+impl<S: AsyncRead + AsyncWrite + Unpin> Connection<S> {
+    /// Does the handshake over an already open stream.
+    ///
+    /// The server answers the key with blank lines, then starts the game stream; the handshake
+    /// is over at its first byte, which is left unread along with everything after it.
+    pub async fn handshake(stream: S, key: &str, handshake: &Handshake) -> Result<Self, Error> {
+        let mut stream = BufReader::new(stream);
+
+        let banner = read_line(&mut stream, handshake.timeout, Step::Banner).await?;
+        tracing::debug!(banner = banner.trim_end(), "connected to the game server");
+
+        let frontend = handshake.frontend_line();
+        tracing::debug!(frontend, "sending key");
+        stream
+            .write_all(format!("{key}\n{frontend}\n").as_bytes())
+            .await?;
+
+        let first = timeout(handshake.timeout, skip_blank(&mut stream))
+            .await
+            .map_err(|_| Error::Timeout(Step::Replies))??;
+        // the XML stream opens with a tag; anything else is an error message in place of the game
+        if handshake.xml && first != b'<' {
+            let reply = read_line(&mut stream, handshake.timeout, Step::Replies).await?;
+            return Err(Error::UnexpectedReply(reply.trim_end().to_owned()));
+        }
+
+        if handshake.xml {
+            stream.write_all(b"/XML\n").await?;
+        }
+
+        Ok(Self { stream, banner })
+    }
+
+    /// The line the server greeted us with.
+    pub fn banner(&self) -> &str {
+        &self.banner
+    }
+
+    /// Sends a command, adding the newline.
+    pub async fn send(&mut self, command: &str) -> Result<(), Error> {
+        self.stream
+            .write_all(format!("{command}\n").as_bytes())
+            .await?;
+        Ok(())
+    }
+
+    /// The game stream, from its very first byte.
+    pub fn get_mut(&mut self) -> &mut BufReader<S> {
+        &mut self.stream
+    }
+
+    pub fn into_inner(self) -> BufReader<S> {
+        self.stream
+    }
+}
+
+/// Skips blank lines up to the first byte of anything else, which is returned but not consumed.
+async fn skip_blank<S: AsyncRead + Unpin>(stream: &mut BufReader<S>) -> Result<u8, Error> {
+    loop {
+        let buf = stream.fill_buf().await?;
+        if buf.is_empty() {
+            return Err(Error::Closed(Step::Replies));
+        }
+        match buf.iter().position(|b| !b.is_ascii_whitespace()) {
+            Some(n) => {
+                let first = buf[n];
+                stream.consume(n);
+                return Ok(first);
+            }
+            None => {
+                let n = buf.len();
+                stream.consume(n);
+            }
+        }
+    }
+}
+
+async fn read_line<S: AsyncRead + Unpin>(
+    stream: &mut BufReader<S>,
+    limit: Duration,
+    step: Step,
+) -> Result<String, Error> {
+    let mut line = String::new();
+    let read = timeout(limit, stream.read_line(&mut line))
+        .await
+        .map_err(|_| Error::Timeout(step))??;
+    if read == 0 {
+        return Err(Error::Closed(step));
+    }
+    Ok(line)
+}
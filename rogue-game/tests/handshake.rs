@@ -0,0 +1,127 @@
+use std::time::Duration;
+
+use rogue_game::{Connection, Error, Frontend, Handshake, Step};
+use rogue_stormfront::{Events, GameEvent};
+use tokio::io::{AsyncBufReadExt, AsyncReadExt, AsyncWriteExt, BufReader, DuplexStream, duplex};
+
+/// Plays the game server's side of the handshake, returning what the client sent.
+async fn server(stream: DuplexStream, replies: &'static [&'static str]) -> Vec<String> {
+    let mut stream = BufReader::new(stream);
+    stream.write_all(b"banner\n").await.unwrap();
+
+    let mut sent = Vec::new();
+    for _ in 0..2 {
+        let mut line = String::new();
+        stream.read_line(&mut line).await.unwrap();
+        sent.push(line);
+    }
+    for reply in replies {
+        stream.write_all(reply.as_bytes()).await.unwrap();
+    }
+
+    let mut line = String::new();
+    stream.read_line(&mut line).await.unwrap();
+    sent.push(line);
+    sent
+}
+
+#[tokio::test]
+async fn xml_handshake() {
+    let (client, stream) = duplex(1024);
+    let server = tokio::spawn(server(
+        stream,
+        &[
+            "\n",
+            "<mode id=\"GAME\"/>\n",
+            "<settingsInfo instance=\"GS4\"/>\n",
+        ],
+    ));
+
+    let conn = Connection::handshake(client, "4e2a7ad3f1c90e6b", &Handshake::default())
+        .await
+        .unwrap();
+    assert_eq!(conn.banner(), "banner\n");
+
+    // the tags sent during the handshake are the start of the game stream
+    let mut events = Events::new(conn.into_inner());
+    assert_eq!(
+        events.next().await.unwrap(),
+        Some(GameEvent::Mode("GAME".to_owned()))
+    );
+    assert_eq!(
+        server.await.unwrap(),
+        [
+            "4e2a7ad3f1c90e6b\n",
+            "/FE:WRAYTH /VERSION:1.0.1.28 /P:WIN_UNKNOWN /XML\n",
+            "/XML\n",
+        ]
+    );
+}
+
+#[tokio::test]
+async fn plain_handshake() {
+    let (client, stream) = duplex(1024);
+    let server = tokio::spawn(server(stream, &["Welcome\n"]));
+
+    let handshake = Handshake {
+        frontend: Frontend::Wizard,
+        version: "1.0".to_owned(),
+        xml: false,
+        ..Handshake::default()
+    };
+    let mut conn = Connection::handshake(client, "key", &handshake)
+        .await
+        .unwrap();
+    let mut line = String::new();
+    conn.get_mut().read_line(&mut line).await.unwrap();
+    assert_eq!(line, "Welcome\n");
+    conn.send("look").await.unwrap();
+
+    assert_eq!(
+        server.await.unwrap(),
+        [
+            "key\n",
+            "/FE:WIZARD /VERSION:1.0 /P:WIN_UNKNOWN\n",
+            "look\n"
+        ]
+    );
+}
+
+#[tokio::test]
+async fn text_instead_of_xml_is_rejected() {
+    let (client, stream) = duplex(1024);
+    tokio::spawn(server(
+        stream,
+        &["Sorry, that key is invalid.\n", "\n", "\n"],
+    ));
+
+    let res = Connection::handshake(client, "key", &Handshake::default()).await;
+    assert!(matches!(res, Err(Error::UnexpectedReply(_))));
+}
+
+#[tokio::test(start_paused = true)]
+async fn hang_ups_and_silence_are_errors() {
+    let (client, mut stream) = duplex(1024);
+    tokio::spawn(async move {
+        stream.write_all(b"banner\n").await.unwrap();
+        stream.shutdown().await.unwrap();
+        // hold on to our end so the client can still write
+        stream.read_to_end(&mut Vec::new()).await.unwrap();
+    });
+    let res = Connection::handshake(client, "key", &Handshake::default()).await;
+    assert!(matches!(res, Err(Error::Closed(Step::Replies))));
+
+    let (client, _stream) = duplex(1024);
+    let handshake = Handshake {
+        timeout: Duration::from_secs(1),
+        ..Handshake::default()
+    };
+    let res = Connection::handshake(client, "key", &handshake).await;
+    assert!(matches!(res, Err(Error::Timeout(Step::Banner))));
+
+    // blank lines don't count as the game starting
+    let (client, mut stream) = duplex(1024);
+    stream.write_all(b"banner\n\n\n").await.unwrap();
+    let res = Connection::handshake(client, "key", &handshake).await;
+    assert!(matches!(res, Err(Error::Timeout(Step::Replies))));
+}
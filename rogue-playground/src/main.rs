@@ -1,6 +1,8 @@
 use std::io::{Write, stdin, stdout};
 
 use eaccess::Message;
+use rogue_game::{Connection, Handshake};
+use rogue_stormfront::{Events, GameEvent, Lines, MAIN, Router};
 use tokio::{
     io::{AsyncBufReadExt, AsyncWriteExt, BufReader},
     net::TcpStream,
@@ -11,17 +13,13 @@ async fn main() -> anyhow::Result<()> {
     setup_tracing()?;
 
     let mut account = String::new();
-    let mut password = String::new();
 
     print!("Account name: ");
     stdout().flush()?;
     stdin().read_line(&mut account)?;
     account = account.replace("\n", "");
 
-    print!("Password: ");
-    stdout().flush()?;
-    stdin().read_line(&mut password)?;
-    password = password.replace("\n", "");
+    let password = rogue_vault::prompt_secret("Password: ")?;
 
     tracing::trace!("connecting to {:?}", eaccess::ENDPOINT);
     let tcp = TcpStream::connect(eaccess::ENDPOINT).await?;
@@ -60,7 +58,8 @@ async fn main() -> anyhow::Result<()> {
             continue;
         }
 
-        tcp.write_all(eaccess::G::out(node).as_bytes()).await?;
+        tcp.write_all(eaccess::G::out(&(*node).into()).as_bytes())
+            .await?;
 
         let mut buf = String::new();
         tcp.read_line(&mut buf).await?;
@@ -84,7 +83,6 @@ async fn main() -> anyhow::Result<()> {
             let res = eaccess::L::parse(buf.as_str())?;
             tracing::trace!("{res:?}");
             access = Some((res.game_host.to_owned(), res.game_port, res.key.to_owned()));
-            tracing::trace!("{access:?}");
         }
     }
 
@@ -92,37 +90,46 @@ async fn main() -> anyhow::Result<()> {
     let Some((host, port, key)) = access else {
         anyhow::bail!("where's my stuff");
     };
-    let tcp = TcpStream::connect((host.as_str(), port as _)).await?;
-    let mut tcp = BufReader::new(tcp);
-
-    let mut line = String::new();
-    tcp.read_line(&mut line).await?;
-    tracing::trace!("0 -> {line}");
-
-    tcp.write_all(format!("{key}\n/FE:WRAYTH /VERSION:1.0.1.28 /P:WIN_UNKNOWN /XML\n").as_bytes())
-        .await?;
-    for i in 0..3 {
-        let mut line = String::new();
-        tcp.read_line(&mut line).await?;
-        tracing::trace!("{i} -> {line}");
+    let port = u16::try_from(port)?;
+    let conn = Connection::connect((host.as_str(), port), &key, &Handshake::default()).await?;
+    let mut stream = Events::new(conn.into_inner());
+    let mut lines = Lines::new();
+    // only main is printed; thoughts, speech and the like are dropped
+    let mut router = Router::new();
+    let mut main = router.subscribe(MAIN);
+
+    while let Some(event) = stream.next().await? {
+        router.route(event);
+        while let Ok(event) = main.try_recv() {
+            print_event(&mut lines, &event);
+        }
+        stdout().flush()?;
     }
 
-    tcp.write_all(b"/XML\n").await?;
-
-    let mut buffer = String::new();
+    Ok(())
+}
 
-    loop {
-        buffer.clear();
-        tcp.read_line(&mut buffer).await?;
-        print!("{buffer}")
+fn print_event(lines: &mut Lines, event: &GameEvent) {
+    lines.push(event);
+    // the prompt has no newline of its own; the one that follows it ends the line instead
+    let prompt = matches!(event, GameEvent::Prompt { .. });
+    if prompt {
+        lines.flush();
+    }
+    let mut line = lines.next_line();
+    while let Some(current) = line {
+        for span in &current.spans {
+            if span.format.bold {
+                print!("\x1b[1m{}\x1b[0m", span.text);
+            } else {
+                print!("{}", span.text);
+            }
+        }
+        line = lines.next_line();
+        if !prompt || line.is_some() {
+            println!();
+        }
     }
-
-    // get node for gs4
-    // let Some((gs4_node, _)) = res.0.iter().find(|(_, name)| name == &"GemStone IV") else {
-    //     anyhow::bail!("GemStone IV server not found")
-    // };
-
-    // Ok(())
 }
 
 fn setup_tracing() -> anyhow::Result<()> {
@@ -0,0 +1,174 @@
+//! The panels the game drives with `<openDialog>` and `<dialogData>`: vitals bars, injuries,
+//! active spells, experience, combat, encumbrance, minivitals and the rest.
+//!
+//! All of them are made of the same few widgets, so they're kept generically, by dialog id and
+//! widget id, and it's up to the frontend how to draw each one.
+
+use std::collections::BTreeMap;
+
+use crate::GameEvent;
+
+/// A widget in a dialog.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Widget {
+    /// `<progressBar value text>`, with the value a percentage
+    ProgressBar { value: u32, text: String },
+    /// `<label value>`
+    Label { value: String },
+    /// `<link value cmd echo>`, text that sends `cmd`
+    Link {
+        value: String,
+        cmd: Option<String>,
+        echo: Option<String>,
+    },
+    /// `<image name cmd>`
+    Image { name: String, cmd: Option<String> },
+    /// `<skin name>`
+    Skin { name: String },
+    /// `<cmdButton value cmd echo>`, a button that sends `cmd`
+    CmdButton {
+        value: String,
+        cmd: Option<String>,
+        echo: Option<String>,
+    },
+}
+
+/// Where a widget goes in its dialog, as sent. Positions and sizes are pixels or percentages,
+/// e.g. "10" or "0%"; the anchors are the ids of the widgets it's placed against.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Layout {
+    pub left: Option<String>,
+    pub top: Option<String>,
+    pub width: Option<String>,
+    pub height: Option<String>,
+    pub align: Option<String>,
+    pub anchor_left: Option<String>,
+    pub anchor_top: Option<String>,
+    pub justify: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Dialog {
+    /// from `<openDialog>`, as are the fields up to `widgets`, for dialogs that have been opened
+    pub title: Option<String>,
+    /// `type`, e.g. dynamic
+    pub kind: Option<String>,
+    /// the window it goes in, e.g. main
+    pub location: Option<String>,
+    pub width: Option<String>,
+    pub height: Option<String>,
+    pub resident: bool,
+    /// in the order they first came, by id
+    pub widgets: Vec<(String, Widget, Layout)>,
+}
+
+impl Dialog {
+    pub fn widget(&self, id: &str) -> Option<&Widget> {
+        self.widgets
+            .iter()
+            .find(|(i, ..)| i == id)
+            .map(|(_, w, _)| w)
+    }
+
+    pub fn layout(&self, id: &str) -> Option<&Layout> {
+        self.widgets.iter().find(|(i, ..)| i == id).map(|(.., l)| l)
+    }
+}
+
+/// What happened to a dialog.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DialogEvent {
+    /// `<openDialog>`
+    Opened(String),
+    /// `<dialogData clear='t'>` removed every widget
+    Cleared(String),
+    /// a widget was added or changed
+    Updated { dialog: String, widget: String },
+}
+
+/// Every dialog the game has sent, by id. Feed it every event with
+/// [`apply`](Dialogs::apply); each call says what happened.
+#[derive(Debug, Clone, Default)]
+pub struct Dialogs {
+    pub dialogs: BTreeMap<String, Dialog>,
+    /// the dialogs we're inside, innermost last
+    open: Vec<String>,
+}
+
+impl Dialogs {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn get(&self, id: &str) -> Option<&Dialog> {
+        self.dialogs.get(id)
+    }
+
+    pub fn apply(&mut self, event: &GameEvent) -> Vec<DialogEvent> {
+        let mut events = Vec::new();
+        match event {
+            GameEvent::OpenDialog {
+                id,
+                title,
+                kind,
+                location,
+                width,
+                height,
+                resident,
+            } => {
+                let dialog = self.dialogs.entry(id.clone()).or_default();
+                dialog.title = title.clone();
+                dialog.kind = kind.clone();
+                dialog.location = location.clone();
+                dialog.width = width.clone();
+                dialog.height = height.clone();
+                dialog.resident = *resident;
+                self.open.push(id.clone());
+                events.push(DialogEvent::Opened(id.clone()));
+            }
+            GameEvent::DialogData { id, clear } => {
+                let dialog = self.dialogs.entry(id.clone()).or_default();
+                if *clear {
+                    dialog.widgets.clear();
+                    events.push(DialogEvent::Cleared(id.clone()));
+                }
+                self.open.push(id.clone());
+            }
+            GameEvent::OpenDialogEnd | GameEvent::DialogDataEnd => {
+                self.open.pop();
+            }
+            GameEvent::ProgressBar {
+                id,
+                value,
+                text,
+                layout,
+            } => {
+                let widget = Widget::ProgressBar {
+                    value: *value,
+                    text: text.clone(),
+                };
+                events.extend(self.update(id, widget, layout.clone()));
+            }
+            GameEvent::Widget { id, widget, layout } => {
+                events.extend(self.update(id, widget.clone(), layout.clone()));
+            }
+            _ => {}
+        }
+        events
+    }
+
+    /// Sets a widget in the innermost dialog; widgets outside any dialog are left alone.
+    fn update(&mut self, id: &str, widget: Widget, layout: Layout) -> Option<DialogEvent> {
+        let dialog_id = self.open.last()?;
+        let dialog = self.dialogs.entry(dialog_id.clone()).or_default();
+        match dialog.widgets.iter_mut().find(|(i, ..)| i == id) {
+            Some((_, old, old_layout)) if *old == widget && *old_layout == layout => return None,
+            Some((_, old, old_layout)) => (*old, *old_layout) = (widget, layout),
+            None => dialog.widgets.push((id.to_owned(), widget, layout)),
+        }
+        Some(DialogEvent::Updated {
+            dialog: dialog_id.clone(),
+            widget: id.to_owned(),
+        })
+    }
+}
@@ -0,0 +1,472 @@
+//! Turns tokens into the elements of the protocol.
+//!
+//! Events follow the stream as it comes: elements that wrap other text and tags, like links and
+//! components, are a start event and an end event with the contents in between. Elements whose
+//! contents are just a value, like the prompt or what's in a hand, are gathered into one event.
+//! Anything missing an attribute it needs comes through as [`GameEvent::Unknown`].
+
+use std::collections::VecDeque;
+
+use tokio::io::AsyncRead;
+
+use crate::{Layout, Reader, Tag, Token, Widget};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum GameEvent {
+    /// Text, in whatever stream and style is current. A line may come in several pieces.
+    Text(String),
+    /// `<prompt time>`, with the server's clock in epoch seconds
+    Prompt {
+        time: Option<u64>,
+        text: String,
+    },
+    /// `<roundTime value>`, when roundtime ends, in server epoch seconds
+    RoundTime(u64),
+    /// `<castTime value>`, when casttime ends, in server epoch seconds
+    CastTime(u64),
+    /// `<progressBar id value text>`, with the value a percentage
+    ProgressBar {
+        id: String,
+        value: u32,
+        text: String,
+        layout: Layout,
+    },
+    /// `<indicator id visible>`, e.g. IconSTANDING
+    Indicator {
+        id: String,
+        visible: bool,
+    },
+    /// `<compass>`, the `value` of each `<dir>` in it
+    Compass(Vec<String>),
+    /// `<pushStream id>`: text goes to this stream until the next [`PopStream`](Self::PopStream)
+    PushStream(String),
+    PopStream,
+    /// `<clearStream id>`
+    ClearStream(String),
+    /// `<streamWindow id title subtitle>`
+    StreamWindow {
+        id: String,
+        title: Option<String>,
+        subtitle: Option<String>,
+    },
+    /// `<component id>` or `<compDef id>`, e.g. `room desc`
+    Component(String),
+    ComponentEnd,
+    /// `<style id>`; an empty id ends the style
+    Style(String),
+    PushBold,
+    PopBold,
+    /// `<preset id>`, e.g. speech or roomDesc
+    Preset(String),
+    PresetEnd,
+    /// `<a exist noun>`, a game object
+    Link {
+        exist: Option<String>,
+        noun: Option<String>,
+    },
+    LinkEnd,
+    /// `<d cmd>`, a command; without `cmd`, the text is the command
+    Command(Option<String>),
+    CommandEnd,
+    /// `<spell>`, the prepared spell, or "None"
+    Spell(String),
+    /// `<left>`
+    Left(Hand),
+    /// `<right>`
+    Right(Hand),
+    /// `<nav rm>`, sent on moving rooms
+    Nav(Option<String>),
+    /// `<mode id>`, e.g. GAME
+    Mode(String),
+    /// `<settingsInfo>`
+    SettingsInfo {
+        instance: Option<String>,
+    },
+    /// `<dialogData id clear>`
+    DialogData {
+        id: String,
+        clear: bool,
+    },
+    DialogDataEnd,
+    /// `<openDialog type id title location width height resident>`
+    OpenDialog {
+        id: String,
+        title: Option<String>,
+        /// `type`, e.g. dynamic
+        kind: Option<String>,
+        /// the window it goes in, e.g. main
+        location: Option<String>,
+        width: Option<String>,
+        height: Option<String>,
+        /// whether the frontend should keep it open
+        resident: bool,
+    },
+    OpenDialogEnd,
+    /// `<output class>`, e.g. mono; an empty class ends it
+    Output(String),
+    /// `<resource picture>`
+    Resource(String),
+    /// `<container id title target>`, a container window; `target` is `#` and its exist id
+    Container {
+        id: String,
+        title: Option<String>,
+        target: Option<String>,
+    },
+    /// `<clearContainer id>`
+    ClearContainer(String),
+    /// `<exposeContainer id>`
+    ExposeContainer(String),
+    /// `<inv id>`, a line of what's in a container
+    Inv(String),
+    InvEnd,
+    /// `<label>`, `<link>`, `<image>`, `<skin>` or `<cmdButton>`, as found in dialogs.
+    /// `<progressBar>` is [`ProgressBar`](Self::ProgressBar).
+    Widget {
+        id: String,
+        widget: Widget,
+        layout: Layout,
+    },
+    /// Any other element.
+    Unknown(Tag),
+    /// The end of an element that came through as [`Unknown`](Self::Unknown).
+    UnknownEnd(String),
+}
+
+/// What's in a hand.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Hand {
+    pub exist: Option<String>,
+    pub noun: Option<String>,
+    /// e.g. "a broadsword", or "Empty"
+    pub name: String,
+}
+
+impl Hand {
+    pub fn is_empty(&self) -> bool {
+        self.exist.is_none()
+    }
+}
+
+/// An element whose contents are still being read.
+#[derive(Debug)]
+enum Pending {
+    Text(Tag, String),
+    Compass(Vec<String>),
+}
+
+/// Turns tokens into events. Works like the [`Tokenizer`](crate::Tokenizer):
+/// [`push`](Parser::push) tokens in and take events out with
+/// [`next_event`](Parser::next_event).
+#[derive(Debug, Default)]
+pub struct Parser {
+    pending: Option<Pending>,
+    events: VecDeque<GameEvent>,
+}
+
+impl Parser {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn push(&mut self, token: Token) {
+        match (&mut self.pending, token) {
+            (Some(Pending::Text(tag, text)), Token::End(name)) if name == tag.name => {
+                let tag = std::mem::replace(tag, empty());
+                let text = std::mem::take(text);
+                self.pending = None;
+                self.events.push_back(valued(tag, text));
+            }
+            (Some(Pending::Text(_, text)), Token::Text(more)) => match more.find(['\r', '\n']) {
+                None => text.push_str(&more),
+                // contents never span lines, so this one wasn't closed
+                Some(i) => {
+                    text.push_str(&more[..i]);
+                    self.flush();
+                    self.events.push_back(GameEvent::Text(more[i..].to_owned()));
+                }
+            },
+            (Some(Pending::Compass(dirs)), Token::Start(tag)) if tag.name == "dir" => {
+                dirs.extend(tag.attr("value").map(str::to_owned));
+            }
+            (Some(Pending::Compass(_)), Token::End(name)) if name == "dir" => {}
+            (Some(Pending::Compass(_)), Token::End(name)) if name == "compass" => self.flush(),
+            (Some(_), token) => {
+                self.flush();
+                self.push(token);
+            }
+            (None, Token::Text(text)) => self.events.push_back(GameEvent::Text(text)),
+            (None, Token::Start(tag)) => self.start(tag),
+            (None, Token::End(name)) => self.end(name),
+        }
+    }
+
+    /// Marks the end of input, so an element that was never closed comes out.
+    pub fn finish(&mut self) {
+        self.flush();
+    }
+
+    pub fn next_event(&mut self) -> Option<GameEvent> {
+        self.events.pop_front()
+    }
+
+    fn flush(&mut self) {
+        match self.pending.take() {
+            Some(Pending::Text(tag, text)) => self.events.push_back(valued(tag, text)),
+            Some(Pending::Compass(dirs)) => self.events.push_back(GameEvent::Compass(dirs)),
+            None => {}
+        }
+    }
+
+    fn start(&mut self, tag: Tag) {
+        let event = match tag.name.as_str() {
+            "prompt" | "spell" | "left" | "right" => {
+                if tag.self_closing {
+                    valued(tag, String::new())
+                } else {
+                    self.pending = Some(Pending::Text(tag, String::new()));
+                    return;
+                }
+            }
+            "compass" => {
+                if tag.self_closing {
+                    GameEvent::Compass(Vec::new())
+                } else {
+                    self.pending = Some(Pending::Compass(Vec::new()));
+                    return;
+                }
+            }
+            _ => {
+                let self_closing = tag.self_closing;
+                let event = element(tag);
+                // `<dialogData id clear/>` and the like: close them straight away so start and
+                // end events always pair up
+                let end = if self_closing { closing(&event) } else { None };
+                self.events.push_back(event);
+                self.events.extend(end);
+                return;
+            }
+        };
+        self.events.push_back(event);
+    }
+
+    fn end(&mut self, name: String) {
+        let event = match name.as_str() {
+            "component" | "compDef" => GameEvent::ComponentEnd,
+            "preset" => GameEvent::PresetEnd,
+            "a" => GameEvent::LinkEnd,
+            "d" => GameEvent::CommandEnd,
+            "dialogData" => GameEvent::DialogDataEnd,
+            "openDialog" => GameEvent::OpenDialogEnd,
+            "inv" => GameEvent::InvEnd,
+            // elements that are always gathered or self-closing; a stray end means nothing
+            "prompt" | "spell" | "left" | "right" | "compass" | "dir" | "roundTime"
+            | "castTime" | "progressBar" | "indicator" | "pushStream" | "popStream"
+            | "clearStream" | "streamWindow" | "style" | "pushBold" | "popBold" | "nav"
+            | "mode" | "settingsInfo" | "output" | "resource" | "container" | "clearContainer"
+            | "exposeContainer" | "label" | "link" | "image" | "skin" | "cmdButton" => return,
+            _ => GameEvent::UnknownEnd(name),
+        };
+        self.events.push_back(event);
+    }
+}
+
+fn empty() -> Tag {
+    Tag {
+        name: String::new(),
+        attrs: Vec::new(),
+        self_closing: false,
+    }
+}
+
+fn owned(tag: &Tag, name: &str) -> Option<String> {
+    tag.attr(name).map(str::to_owned)
+}
+
+fn layout(tag: &Tag) -> Layout {
+    Layout {
+        left: owned(tag, "left"),
+        top: owned(tag, "top"),
+        width: owned(tag, "width"),
+        height: owned(tag, "height"),
+        align: owned(tag, "align"),
+        anchor_left: owned(tag, "anchor_left"),
+        anchor_top: owned(tag, "anchor_top"),
+        justify: owned(tag, "justify"),
+    }
+}
+
+/// An element gathered with its contents.
+fn valued(tag: Tag, text: String) -> GameEvent {
+    let hand = |tag: &Tag| Hand {
+        exist: owned(tag, "exist"),
+        noun: owned(tag, "noun"),
+        name: text.clone(),
+    };
+    match tag.name.as_str() {
+        "prompt" => GameEvent::Prompt {
+            time: tag.attr("time").and_then(|t| t.parse().ok()),
+            text,
+        },
+        "spell" => GameEvent::Spell(text),
+        "left" => GameEvent::Left(hand(&tag)),
+        "right" => GameEvent::Right(hand(&tag)),
+        _ => GameEvent::Unknown(tag),
+    }
+}
+
+/// An element that stands on its own, or opens one with contents to follow.
+fn element(tag: Tag) -> GameEvent {
+    let event = match tag.name.as_str() {
+        "roundTime" => tag
+            .attr("value")
+            .and_then(|v| v.parse().ok())
+            .map(GameEvent::RoundTime),
+        "castTime" => tag
+            .attr("value")
+            .and_then(|v| v.parse().ok())
+            .map(GameEvent::CastTime),
+        "progressBar" => tag.attr("id").and_then(|id| {
+            Some(GameEvent::ProgressBar {
+                id: id.to_owned(),
+                value: tag.attr("value")?.parse().ok()?,
+                text: owned(&tag, "text").unwrap_or_default(),
+                layout: layout(&tag),
+            })
+        }),
+        "indicator" => tag.attr("id").map(|id| GameEvent::Indicator {
+            id: id.to_owned(),
+            visible: tag.attr("visible") == Some("y"),
+        }),
+        "pushStream" => owned(&tag, "id").map(GameEvent::PushStream),
+        "popStream" => Some(GameEvent::PopStream),
+        "clearStream" => owned(&tag, "id").map(GameEvent::ClearStream),
+        "streamWindow" => tag.attr("id").map(|id| GameEvent::StreamWindow {
+            id: id.to_owned(),
+            title: owned(&tag, "title"),
+            subtitle: owned(&tag, "subtitle"),
+        }),
+        "component" | "compDef" => owned(&tag, "id").map(GameEvent::Component),
+        "style" => Some(GameEvent::Style(owned(&tag, "id").unwrap_or_default())),
+        "pushBold" => Some(GameEvent::PushBold),
+        "popBold" => Some(GameEvent::PopBold),
+        "preset" => owned(&tag, "id").map(GameEvent::Preset),
+        "a" => Some(GameEvent::Link {
+            exist: owned(&tag, "exist"),
+            noun: owned(&tag, "noun"),
+        }),
+        "d" => Some(GameEvent::Command(owned(&tag, "cmd"))),
+        "nav" => Some(GameEvent::Nav(owned(&tag, "rm"))),
+        "mode" => owned(&tag, "id").map(GameEvent::Mode),
+        "settingsInfo" => Some(GameEvent::SettingsInfo {
+            instance: owned(&tag, "instance"),
+        }),
+        "dialogData" => tag.attr("id").map(|id| GameEvent::DialogData {
+            id: id.to_owned(),
+            clear: tag.attr("clear") == Some("t"),
+        }),
+        "openDialog" => tag.attr("id").map(|id| GameEvent::OpenDialog {
+            id: id.to_owned(),
+            title: owned(&tag, "title"),
+            kind: owned(&tag, "type"),
+            location: owned(&tag, "location"),
+            width: owned(&tag, "width"),
+            height: owned(&tag, "height"),
+            resident: tag.attr("resident") == Some("true"),
+        }),
+        "output" => Some(GameEvent::Output(owned(&tag, "class").unwrap_or_default())),
+        "resource" => owned(&tag, "picture").map(GameEvent::Resource),
+        "container" => tag.attr("id").map(|id| GameEvent::Container {
+            id: id.to_owned(),
+            title: owned(&tag, "title"),
+            target: owned(&tag, "target"),
+        }),
+        "clearContainer" => owned(&tag, "id").map(GameEvent::ClearContainer),
+        "exposeContainer" => owned(&tag, "id").map(GameEvent::ExposeContainer),
+        "inv" => owned(&tag, "id").map(GameEvent::Inv),
+        "label" | "link" | "image" | "skin" | "cmdButton" => tag.attr("id").map(|id| {
+            let value = || owned(&tag, "value").unwrap_or_default();
+            let name = || owned(&tag, "name").unwrap_or_default();
+            let widget = match tag.name.as_str() {
+                "label" => Widget::Label { value: value() },
+                "link" => Widget::Link {
+                    value: value(),
+                    cmd: owned(&tag, "cmd"),
+                    echo: owned(&tag, "echo"),
+                },
+                "image" => Widget::Image {
+                    name: name(),
+                    cmd: owned(&tag, "cmd"),
+                },
+                "skin" => Widget::Skin { name: name() },
+                _ => Widget::CmdButton {
+                    value: value(),
+                    cmd: owned(&tag, "cmd"),
+                    echo: owned(&tag, "echo"),
+                },
+            };
+            GameEvent::Widget {
+                id: id.to_owned(),
+                widget,
+                layout: layout(&tag),
+            }
+        }),
+        _ => None,
+    };
+    event.unwrap_or(GameEvent::Unknown(tag))
+}
+
+/// The end event for an element that has one.
+fn closing(event: &GameEvent) -> Option<GameEvent> {
+    Some(match event {
+        GameEvent::Component(_) => GameEvent::ComponentEnd,
+        GameEvent::Preset(_) => GameEvent::PresetEnd,
+        GameEvent::Link { .. } => GameEvent::LinkEnd,
+        GameEvent::Command(_) => GameEvent::CommandEnd,
+        GameEvent::DialogData { .. } => GameEvent::DialogDataEnd,
+        GameEvent::OpenDialog { .. } => GameEvent::OpenDialogEnd,
+        GameEvent::Inv(_) => GameEvent::InvEnd,
+        GameEvent::Unknown(tag) => GameEvent::UnknownEnd(tag.name.clone()),
+        _ => return None,
+    })
+}
+
+/// Reads events from an async stream.
+pub struct Events<R> {
+    reader: Reader<R>,
+    parser: Parser,
+    done: bool,
+}
+
+impl<R: AsyncRead + Unpin> Events<R> {
+    pub fn new(inner: R) -> Self {
+        Self {
+            reader: Reader::new(inner),
+            parser: Parser::new(),
+            done: false,
+        }
+    }
+
+    /// The next event, or `None` once the stream has ended and everything's been read.
+    pub async fn next(&mut self) -> std::io::Result<Option<GameEvent>> {
+        loop {
+            if let Some(event) = self.parser.next_event() {
+                return Ok(Some(event));
+            }
+            if self.done {
+                return Ok(None);
+            }
+
+            match self.reader.next().await? {
+                Some(token) => self.parser.push(token),
+                None => {
+                    self.parser.finish();
+                    self.done = true;
+                }
+            }
+        }
+    }
+
+    pub fn into_inner(self) -> R {
+        self.reader.into_inner()
+    }
+}
@@ -0,0 +1,190 @@
+//! What the character is carrying: the hands, worn items from the `inv` stream, and the contents
+//! of containers from `<container>` and `<inv>`.
+
+use std::collections::BTreeMap;
+
+use crate::{Change, GameEvent, Hand, Object, state::replace};
+
+/// A container window, e.g. the stow container.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Container {
+    pub title: Option<String>,
+    /// the container's exist id
+    pub exist: Option<String>,
+    /// whether `<exposeContainer>` has asked for it to be shown
+    pub exposed: bool,
+    pub contents: Vec<Object>,
+}
+
+/// Where an item is.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Location<'a> {
+    Left,
+    Right,
+    Worn,
+    /// by container id
+    Container(&'a str),
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Inventory {
+    pub left: Hand,
+    pub right: Hand,
+    /// from the last listing in the `inv` stream
+    pub worn: Vec<Object>,
+    /// by container id
+    pub containers: BTreeMap<String, Container>,
+}
+
+impl Inventory {
+    pub fn container(&self, id: &str) -> Option<&Container> {
+        self.containers.get(id)
+    }
+
+    /// Everything worn or in a container; the hands are [`left`](Self::left) and
+    /// [`right`](Self::right).
+    pub fn items(&self) -> impl Iterator<Item = (Location<'_>, &Object)> {
+        let worn = self.worn.iter().map(|item| (Location::Worn, item));
+        let contained = self.containers.iter().flat_map(|(id, container)| {
+            container
+                .contents
+                .iter()
+                .map(|item| (Location::Container(id), item))
+        });
+        worn.chain(contained)
+    }
+
+    /// Where the item with this exist id is.
+    pub fn locate(&self, exist: &str) -> Option<Location<'_>> {
+        self.find(|e, _| e == Some(exist))
+    }
+
+    /// Where the first item with this noun is, checking the hands first.
+    pub fn find_noun(&self, noun: &str) -> Option<Location<'_>> {
+        self.find(|_, n| n == Some(noun))
+    }
+
+    fn find(&self, matches: impl Fn(Option<&str>, Option<&str>) -> bool) -> Option<Location<'_>> {
+        if matches(self.right.exist.as_deref(), self.right.noun.as_deref()) {
+            return Some(Location::Right);
+        }
+        if matches(self.left.exist.as_deref(), self.left.noun.as_deref()) {
+            return Some(Location::Left);
+        }
+        self.items()
+            .find(|(_, item)| matches(item.exist.as_deref(), item.noun.as_deref()))
+            .map(|(location, _)| location)
+    }
+}
+
+/// Reads the inventory out of the stream; used by [`GameState`](crate::GameState).
+#[derive(Debug, Clone, Default)]
+pub(crate) struct InventoryReader {
+    /// whether text is going to the `inv` stream
+    in_inv: bool,
+    /// worn items listed since the `inv` stream was last cleared
+    worn: Option<Vec<Object>>,
+    /// the `<inv>` being read: its container and the first link in it
+    inv: Option<(String, Option<Object>)>,
+    /// the link being read, in the `inv` stream or an `<inv>`
+    link: Option<Object>,
+}
+
+impl InventoryReader {
+    pub(crate) fn apply(
+        &mut self,
+        inventory: &mut Inventory,
+        event: &GameEvent,
+        changes: &mut Vec<Change>,
+    ) {
+        let mut set = |change: Change, changed: bool| {
+            if changed {
+                changes.push(change);
+            }
+        };
+
+        match event {
+            GameEvent::Left(hand) => set(Change::Left, replace(&mut inventory.left, hand.clone())),
+            GameEvent::Right(hand) => {
+                set(Change::Right, replace(&mut inventory.right, hand.clone()));
+            }
+
+            GameEvent::ClearStream(id) if id == "inv" => self.worn = Some(Vec::new()),
+            GameEvent::PushStream(id) => {
+                self.in_inv = id == "inv";
+                if self.in_inv && self.worn.is_none() {
+                    self.worn = Some(Vec::new());
+                }
+            }
+            GameEvent::PopStream if self.in_inv => {
+                self.in_inv = false;
+                if let Some(worn) = self.worn.take() {
+                    set(Change::Worn, replace(&mut inventory.worn, worn));
+                }
+            }
+
+            GameEvent::Container { id, title, target } => {
+                let container = inventory.containers.entry(id.clone()).or_default();
+                let exist = target
+                    .as_deref()
+                    .map(|t| t.trim_start_matches('#').to_owned());
+                let changed = replace(&mut container.title, title.clone())
+                    | replace(&mut container.exist, exist);
+                set(Change::Container(id.clone()), changed);
+            }
+            GameEvent::ExposeContainer(id) => {
+                let container = inventory.containers.entry(id.clone()).or_default();
+                set(
+                    Change::Container(id.clone()),
+                    replace(&mut container.exposed, true),
+                );
+            }
+            GameEvent::ClearContainer(id) => {
+                let container = inventory.containers.entry(id.clone()).or_default();
+                set(
+                    Change::Container(id.clone()),
+                    replace(&mut container.contents, Vec::new()),
+                );
+            }
+            GameEvent::Inv(id) => self.inv = Some((id.clone(), None)),
+            GameEvent::InvEnd => {
+                let Some((id, Some(item))) = self.inv.take() else {
+                    return;
+                };
+                let container = inventory.containers.entry(id.clone()).or_default();
+                // the first line can be a heading that links to the container itself
+                if item.exist.is_some() && item.exist == container.exist {
+                    return;
+                }
+                container.contents.push(item);
+                set(Change::Container(id), true);
+            }
+
+            GameEvent::Link { exist, noun } if self.in_inv || self.inv.is_some() => {
+                self.link = Some(Object {
+                    exist: exist.clone(),
+                    noun: noun.clone(),
+                    name: String::new(),
+                });
+            }
+            GameEvent::Text(text) => {
+                if let Some(link) = &mut self.link {
+                    link.name.push_str(text);
+                }
+            }
+            GameEvent::LinkEnd => {
+                let Some(link) = self.link.take() else {
+                    return;
+                };
+                if let Some((_, item @ None)) = &mut self.inv {
+                    *item = Some(link);
+                } else if self.in_inv
+                    && let Some(worn) = &mut self.worn
+                {
+                    worn.push(link);
+                }
+            }
+            _ => {}
+        }
+    }
+}
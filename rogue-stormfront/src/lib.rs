@@ -0,0 +1,22 @@
+//! The game side of the Stormfront protocol: the XML-ish stream the game server sends once the
+//! frontend has asked for `/XML`.
+
+mod dialog;
+mod event;
+mod inventory;
+mod room;
+mod router;
+mod span;
+mod state;
+mod timer;
+mod tokenizer;
+
+pub use dialog::{Dialog, DialogEvent, Dialogs, Layout, Widget};
+pub use event::{Events, GameEvent, Hand, Parser};
+pub use inventory::{Container, Inventory, Location};
+pub use room::{Object, Room};
+pub use router::{MAIN, Router, Window};
+pub use span::{Format, Line, Lines, Link, Span};
+pub use state::{Change, GameState, Vital};
+pub use timer::Timers;
+pub use tokenizer::{Reader, Tag, Token, Tokenizer};
@@ -0,0 +1,177 @@
+//! The room the character is in, pieced together from the `room …` components, the roomName
+//! style, `<nav>` and the compass.
+
+use crate::{Change, GameEvent, state::replace};
+
+/// A game object: something in the room, a player, or something carried.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Object {
+    pub exist: Option<String>,
+    pub noun: Option<String>,
+    /// as shown, e.g. "a well"
+    pub name: String,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Room {
+    /// The room number, from `<nav rm>` or a `(u…)` after the name, as GemStone IV shows it.
+    pub id: Option<String>,
+    /// e.g. "[Wehnimer's, Town Square Central]"
+    pub name: String,
+    pub description: String,
+    pub objects: Vec<Object>,
+    pub players: Vec<Object>,
+    /// from the compass, e.g. "n" or "out"
+    pub exits: Vec<String>,
+    /// from the `room exits` component, e.g. "north"
+    pub paths: Vec<String>,
+    /// the `room extra` component, when there is one
+    pub extra: String,
+}
+
+/// Reads the room out of the stream; used by [`GameState`](crate::GameState).
+#[derive(Debug, Clone, Default)]
+pub(crate) struct RoomReader {
+    component: Option<Component>,
+    /// the text since `<style id='roomName'/>`
+    name: Option<String>,
+    /// the last `<nav>`, until the room name that follows it
+    nav: Option<Option<String>>,
+}
+
+#[derive(Debug, Clone, Default)]
+struct Component {
+    id: String,
+    text: String,
+    links: Vec<Object>,
+    /// the link being read
+    link: Option<Object>,
+}
+
+impl RoomReader {
+    pub(crate) fn apply(&mut self, room: &mut Room, event: &GameEvent, changes: &mut Vec<Change>) {
+        let mut set = |change: Change, changed: bool| {
+            if changed {
+                changes.push(change);
+            }
+        };
+
+        match event {
+            GameEvent::Nav(rm) => {
+                // without `rm`, the number (or lack of one) comes with the name
+                if rm.is_some() {
+                    set(Change::RoomId, replace(&mut room.id, rm.clone()));
+                }
+                self.nav = Some(rm.clone());
+            }
+            GameEvent::Compass(exits) => {
+                set(Change::RoomExits, replace(&mut room.exits, exits.clone()));
+            }
+            GameEvent::Style(id) => {
+                if id == "roomName" {
+                    self.name = Some(String::new());
+                } else if let Some(name) = self.name.take() {
+                    let (name, id) = split_id(name.trim());
+                    let nav = self.nav.take();
+                    if id.is_some() || nav.is_some() {
+                        let id = id.map(str::to_owned).or(nav.flatten());
+                        set(Change::RoomId, replace(&mut room.id, id));
+                    }
+                    set(Change::RoomName, replace(&mut room.name, name.to_owned()));
+                }
+            }
+            GameEvent::Component(id) => {
+                self.component = Some(Component {
+                    id: id.clone(),
+                    ..Component::default()
+                });
+            }
+            GameEvent::Link { exist, noun } => {
+                if let Some(component) = &mut self.component {
+                    component.link = Some(Object {
+                        exist: exist.clone(),
+                        noun: noun.clone(),
+                        name: String::new(),
+                    });
+                }
+            }
+            GameEvent::LinkEnd => {
+                if let Some(component) = &mut self.component
+                    && let Some(link) = component.link.take()
+                {
+                    component.links.push(link);
+                }
+            }
+            GameEvent::Text(text) => {
+                if let Some(name) = &mut self.name {
+                    name.push_str(text);
+                }
+                if let Some(component) = &mut self.component {
+                    component.text.push_str(text);
+                    if let Some(link) = &mut component.link {
+                        link.name.push_str(text);
+                    }
+                }
+            }
+            GameEvent::ComponentEnd => {
+                let Some(component) = self.component.take() else {
+                    return;
+                };
+                let text = component.text.trim().to_owned();
+                match component.id.as_str() {
+                    "room desc" => {
+                        set(
+                            Change::RoomDescription,
+                            replace(&mut room.description, text),
+                        );
+                    }
+                    "room objs" => {
+                        set(
+                            Change::RoomObjects,
+                            replace(&mut room.objects, component.links),
+                        );
+                    }
+                    "room players" => {
+                        set(
+                            Change::RoomPlayers,
+                            replace(&mut room.players, component.links),
+                        );
+                    }
+                    "room exits" => {
+                        set(Change::RoomPaths, replace(&mut room.paths, paths(&text)));
+                    }
+                    "room extra" => set(Change::RoomExtra, replace(&mut room.extra, text)),
+                    _ => {}
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Splits "[Town Square] (u7150105)" into the name and the room number.
+fn split_id(name: &str) -> (&str, Option<&str>) {
+    if let Some(rest) = name.strip_suffix(')')
+        && let Some((name, id)) = rest.rsplit_once(" (u")
+        && !id.is_empty()
+        && id.bytes().all(|b| b.is_ascii_digit())
+    {
+        return (name.trim_end(), Some(id));
+    }
+    (name, None)
+}
+
+/// "Obvious paths: north, east, and out." to the paths.
+fn paths(text: &str) -> Vec<String> {
+    let list = text.split_once(':').map_or(text, |(_, list)| list);
+    list.trim()
+        .trim_end_matches('.')
+        .split(',')
+        .map(|path| {
+            let path = path.trim();
+            path.strip_prefix("and ").unwrap_or(path)
+        })
+        .filter(|path| !path.is_empty() && *path != "none")
+        .map(str::to_owned)
+        .collect()
+}
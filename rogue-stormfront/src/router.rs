@@ -0,0 +1,112 @@
+//! Splits the event stream by `<pushStream>` window: thoughts, logons, death, inv, familiar,
+//! speech and the rest, with everything else going to `main`.
+
+use std::collections::{BTreeMap, HashMap};
+
+use tokio::sync::mpsc::{UnboundedReceiver, UnboundedSender, unbounded_channel};
+
+use crate::GameEvent;
+
+/// The stream for everything not pushed to another.
+pub const MAIN: &str = "main";
+
+/// What `<streamWindow>` said about a stream.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Window {
+    pub title: Option<String>,
+    /// e.g. the room name, on main
+    pub subtitle: Option<String>,
+}
+
+/// Sends each event to a channel for the stream it's in.
+///
+/// A stream nobody has subscribed to goes to its fallback, if it has one, and is dropped
+/// otherwise. `<pushStream>` doesn't nest: `<popStream/>` always goes back to main.
+#[derive(Debug, Default)]
+pub struct Router {
+    current: Option<String>,
+    channels: HashMap<String, UnboundedSender<GameEvent>>,
+    fallbacks: HashMap<String, String>,
+    windows: BTreeMap<String, Window>,
+}
+
+impl Router {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sends a stream to another when nobody's subscribed to it, e.g. thoughts to main.
+    pub fn fallback(mut self, stream: impl Into<String>, to: impl Into<String>) -> Self {
+        self.fallbacks.insert(stream.into(), to.into());
+        self
+    }
+
+    /// Everything sent to a stream from now on, replacing any earlier subscription to it.
+    ///
+    /// `<clearStream>` for it comes through as [`GameEvent::ClearStream`]. Drop the receiver to
+    /// unsubscribe.
+    pub fn subscribe(&mut self, stream: impl Into<String>) -> UnboundedReceiver<GameEvent> {
+        let (tx, rx) = unbounded_channel();
+        self.channels.insert(stream.into(), tx);
+        rx
+    }
+
+    /// The stream text is going to.
+    pub fn current(&self) -> &str {
+        self.current.as_deref().unwrap_or(MAIN)
+    }
+
+    pub fn window(&self, stream: &str) -> Option<&Window> {
+        self.windows.get(stream)
+    }
+
+    /// Every stream `<streamWindow>` has told us about.
+    pub fn windows(&self) -> impl Iterator<Item = (&str, &Window)> {
+        self.windows.iter().map(|(id, w)| (id.as_str(), w))
+    }
+
+    pub fn route(&mut self, event: GameEvent) {
+        match event {
+            GameEvent::PushStream(id) => self.current = Some(id),
+            GameEvent::PopStream => self.current = None,
+            GameEvent::StreamWindow {
+                id,
+                title,
+                subtitle,
+            } => {
+                let window = self.windows.entry(id).or_default();
+                // a streamWindow that leaves something out leaves it as it was
+                if title.is_some() {
+                    window.title = title;
+                }
+                if subtitle.is_some() {
+                    window.subtitle = subtitle;
+                }
+            }
+            GameEvent::ClearStream(ref id) => {
+                let id = id.clone();
+                self.send(id, event);
+            }
+            event => self.send(self.current().to_owned(), event),
+        }
+    }
+
+    fn send(&mut self, mut stream: String, mut event: GameEvent) {
+        // fallbacks could go round in circles
+        for _ in 0..=self.fallbacks.len() {
+            if let Some(tx) = self.channels.get(&stream) {
+                match tx.send(event) {
+                    Ok(()) => return,
+                    Err(unsent) => {
+                        self.channels.remove(&stream);
+                        event = unsent.0;
+                    }
+                }
+            }
+            match self.fallbacks.get(&stream) {
+                Some(to) => stream = to.clone(),
+                None => return,
+            }
+        }
+    }
+}
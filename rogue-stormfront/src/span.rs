@@ -0,0 +1,170 @@
+//! Game output as lines of styled text, for whatever draws it.
+
+use std::collections::VecDeque;
+
+use crate::GameEvent;
+
+/// Where clicking on text should lead.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Link {
+    /// `<a exist noun>`, a game object
+    Object {
+        exist: Option<String>,
+        noun: Option<String>,
+    },
+    /// `<d cmd>`, a command to send
+    Command(String),
+}
+
+/// How a span of text is shown.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Format {
+    /// pushBold/popBold
+    pub bold: bool,
+    /// `<preset id>`, e.g. speech, whisper, thought, roomName or roomDesc
+    pub preset: Option<String>,
+    /// `<style id>`
+    pub style: Option<String>,
+    /// `<output class='mono'>`
+    pub mono: bool,
+    pub link: Option<Link>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Span {
+    pub text: String,
+    pub format: Format,
+}
+
+/// A line of output, without its line ending.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Line {
+    pub spans: Vec<Span>,
+}
+
+impl Line {
+    /// The text with the formatting left out.
+    pub fn text(&self) -> String {
+        self.spans.iter().map(|s| s.text.as_str()).collect()
+    }
+
+    fn push(&mut self, text: &str, format: &Format) {
+        if text.is_empty() {
+            return;
+        }
+        match self.spans.last_mut() {
+            Some(last) if last.format == *format => last.text.push_str(text),
+            _ => self.spans.push(Span {
+                text: text.to_owned(),
+                format: format.clone(),
+            }),
+        }
+    }
+}
+
+/// Builds lines from events. [`push`](Lines::push) events in, and take finished lines out with
+/// [`next_line`](Lines::next_line).
+///
+/// Feed it the events for one stream, as split up by a [`Router`](crate::Router).
+#[derive(Debug, Default)]
+pub struct Lines {
+    line: Line,
+    format: Format,
+    bold: usize,
+    presets: Vec<String>,
+    /// for `<d>` without `cmd`: where it started, as the line and the span in it
+    command: Option<(usize, usize)>,
+    /// how many lines have been ended
+    ended: usize,
+    lines: VecDeque<Line>,
+}
+
+impl Lines {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn push(&mut self, event: &GameEvent) {
+        match event {
+            GameEvent::Text(text) => self.text(text),
+            GameEvent::Prompt { text, .. } => self.text(text),
+            GameEvent::PushBold => {
+                self.bold += 1;
+                self.format.bold = true;
+            }
+            GameEvent::PopBold => {
+                self.bold = self.bold.saturating_sub(1);
+                self.format.bold = self.bold > 0;
+            }
+            GameEvent::Preset(id) => {
+                self.presets.push(id.clone());
+                self.format.preset = Some(id.clone());
+            }
+            GameEvent::PresetEnd => {
+                self.presets.pop();
+                self.format.preset = self.presets.last().cloned();
+            }
+            GameEvent::Style(id) => self.format.style = Some(id.clone()).filter(|s| !s.is_empty()),
+            GameEvent::Output(class) => self.format.mono = class == "mono",
+            GameEvent::Link { exist, noun } => {
+                self.format.link = Some(Link::Object {
+                    exist: exist.clone(),
+                    noun: noun.clone(),
+                });
+            }
+            GameEvent::Command(Some(cmd)) => self.format.link = Some(Link::Command(cmd.clone())),
+            GameEvent::Command(None) => {
+                // the command is the text, which hasn't come yet
+                self.format.link = Some(Link::Command(String::new()));
+                self.command = Some((self.ended, self.line.spans.len()));
+            }
+            GameEvent::LinkEnd | GameEvent::CommandEnd => {
+                self.format.link = None;
+                self.end_command();
+            }
+            _ => {}
+        }
+    }
+
+    /// Ends the line in progress, if it has anything in it.
+    pub fn flush(&mut self) {
+        if !self.line.spans.is_empty() {
+            self.end_line();
+        }
+    }
+
+    pub fn next_line(&mut self) -> Option<Line> {
+        self.lines.pop_front()
+    }
+
+    fn text(&mut self, text: &str) {
+        let mut lines = text.split('\n');
+        if let Some(first) = lines.next() {
+            self.line.push(first.trim_end_matches('\r'), &self.format);
+        }
+        for line in lines {
+            self.end_line();
+            self.line.push(line.trim_end_matches('\r'), &self.format);
+        }
+    }
+
+    fn end_line(&mut self) {
+        self.lines.push_back(std::mem::take(&mut self.line));
+        self.ended += 1;
+    }
+
+    fn end_command(&mut self) {
+        let Some((lines, start)) = self.command.take() else {
+            return;
+        };
+        // a command split over lines isn't worth chasing
+        if lines != self.ended {
+            return;
+        }
+        let spans = &mut self.line.spans[start..];
+        let command: String = spans.iter().map(|s| s.text.as_str()).collect();
+        for span in spans {
+            span.format.link = Some(Link::Command(command.clone()));
+        }
+    }
+}
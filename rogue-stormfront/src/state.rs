@@ -0,0 +1,138 @@
+//! What the game has told us so far, kept up to date from the event stream.
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use crate::{
+    GameEvent,
+    inventory::{Inventory, InventoryReader},
+    room::{Room, RoomReader},
+};
+
+/// A bar from `<progressBar>`: health, mana, spirit, stamina, concentration and so on.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Vital {
+    /// percentage
+    pub value: u32,
+    /// e.g. "health 110/118"
+    pub text: String,
+    /// the numbers in the text, when it has them
+    pub current: Option<i64>,
+    pub max: Option<i64>,
+}
+
+impl Vital {
+    fn new(value: u32, text: String) -> Self {
+        let numbers = text
+            .rsplit(' ')
+            .next()
+            .and_then(|n| n.split_once('/'))
+            .and_then(|(c, m)| Some((c.parse().ok()?, m.parse().ok()?)));
+        Self {
+            value,
+            current: numbers.map(|(c, _)| c),
+            max: numbers.map(|(_, m)| m),
+            text,
+        }
+    }
+}
+
+/// Which part of the state changed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Change {
+    /// by progressBar id
+    Vital(String),
+    Left,
+    Right,
+    Spell,
+    /// by indicator id
+    Indicator(String),
+    RoomId,
+    RoomName,
+    RoomDescription,
+    RoomObjects,
+    RoomPlayers,
+    RoomExits,
+    RoomPaths,
+    RoomExtra,
+    Stance,
+    Worn,
+    /// by container id
+    Container(String),
+}
+
+/// The character and their surroundings, as the game has described them.
+///
+/// Feed it every event with [`apply`](GameState::apply); each call says what changed.
+#[derive(Debug, Clone, Default)]
+pub struct GameState {
+    /// by progressBar id
+    pub vitals: BTreeMap<String, Vital>,
+    /// the prepared spell, or "None"
+    pub spell: String,
+    /// the ids of the indicators that are showing, e.g. IconSTANDING
+    pub indicators: BTreeSet<String>,
+    pub room: Room,
+    /// hands, worn items and containers
+    pub inventory: Inventory,
+    /// e.g. "defensive"
+    pub stance: Option<String>,
+
+    room_reader: RoomReader,
+    inventory_reader: InventoryReader,
+}
+
+impl GameState {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Updates the state from one event, returning what changed.
+    pub fn apply(&mut self, event: &GameEvent) -> Vec<Change> {
+        let mut changes = Vec::new();
+        let mut set = |change: Change, changed: bool| {
+            if changed {
+                changes.push(change);
+            }
+        };
+
+        match event {
+            GameEvent::ProgressBar {
+                id, value, text, ..
+            } => {
+                let vital = Vital::new(*value, text.clone());
+                if id == "pbarStance" {
+                    let stance = text.split(" (").next().map(str::to_owned);
+                    set(Change::Stance, replace(&mut self.stance, stance));
+                }
+                let old = self.vitals.insert(id.clone(), vital.clone());
+                set(Change::Vital(id.clone()), old.as_ref() != Some(&vital));
+            }
+            GameEvent::Spell(spell) => {
+                set(Change::Spell, replace(&mut self.spell, spell.clone()));
+            }
+            GameEvent::Indicator { id, visible } => {
+                let changed = if *visible {
+                    self.indicators.insert(id.clone())
+                } else {
+                    self.indicators.remove(id)
+                };
+                set(Change::Indicator(id.clone()), changed);
+            }
+            _ => {}
+        }
+        self.room_reader.apply(&mut self.room, event, &mut changes);
+        self.inventory_reader
+            .apply(&mut self.inventory, event, &mut changes);
+
+        changes
+    }
+}
+
+/// Sets `field`, saying whether that changed it.
+pub(crate) fn replace<T: PartialEq>(field: &mut T, value: T) -> bool {
+    if *field == value {
+        return false;
+    }
+    *field = value;
+    true
+}
@@ -0,0 +1,135 @@
+//! Roundtime and casttime.
+//!
+//! The game gives both as the server time they end at, in epoch seconds, so what's left depends
+//! on the server's clock rather than ours. Every prompt carries the server's time; we pin that to
+//! the moment the prompt arrived and count forward from there on the local monotonic clock.
+
+use std::{
+    sync::Arc,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use tokio::{
+    sync::watch,
+    time::{Instant, timeout},
+};
+
+use crate::GameEvent;
+
+#[derive(Debug, Clone, Copy)]
+struct Clock {
+    /// server time, as of `at`
+    server: Duration,
+    at: Instant,
+    /// the server time minus ours, in seconds
+    offset: f64,
+    roundtime: u64,
+    casttime: u64,
+}
+
+impl Clock {
+    fn server_now(&self) -> Duration {
+        self.server + self.at.elapsed()
+    }
+
+    fn remaining(&self, end: u64) -> Duration {
+        Duration::from_secs(end).saturating_sub(self.server_now())
+    }
+}
+
+/// Roundtime and casttime, kept in step with the server's clock. Clones share the same timers,
+/// so one can be fed events while others wait.
+#[derive(Debug, Clone)]
+pub struct Timers {
+    clock: Arc<watch::Sender<Clock>>,
+}
+
+impl Default for Timers {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Timers {
+    /// Until the first prompt, the server's clock is taken to be ours.
+    pub fn new() -> Self {
+        let clock = Clock {
+            server: local_now(),
+            at: Instant::now(),
+            offset: 0.0,
+            roundtime: 0,
+            casttime: 0,
+        };
+        Self {
+            clock: Arc::new(watch::Sender::new(clock)),
+        }
+    }
+
+    pub fn apply(&self, event: &GameEvent) {
+        match event {
+            GameEvent::Prompt {
+                time: Some(time), ..
+            } => self.clock.send_modify(|clock| {
+                let server = Duration::from_secs(*time);
+                clock.server = server;
+                clock.at = Instant::now();
+                clock.offset = server.as_secs_f64() - local_now().as_secs_f64();
+            }),
+            GameEvent::RoundTime(end) => self.clock.send_modify(|clock| clock.roundtime = *end),
+            GameEvent::CastTime(end) => self.clock.send_modify(|clock| clock.casttime = *end),
+            _ => {}
+        }
+    }
+
+    /// The server's time now, since the epoch.
+    pub fn server_now(&self) -> Duration {
+        self.clock.borrow().server_now()
+    }
+
+    /// How far ahead of our clock the server's is, in seconds, as of the last prompt.
+    pub fn offset(&self) -> f64 {
+        self.clock.borrow().offset
+    }
+
+    pub fn rt_remaining(&self) -> Duration {
+        let clock = self.clock.borrow();
+        clock.remaining(clock.roundtime)
+    }
+
+    pub fn ct_remaining(&self) -> Duration {
+        let clock = self.clock.borrow();
+        clock.remaining(clock.casttime)
+    }
+
+    /// Waits out roundtime, including any more roundtime that's given while waiting.
+    pub async fn wait_rt(&self) {
+        self.wait(|clock| clock.roundtime).await
+    }
+
+    /// Waits out casttime, including any more casttime that's given while waiting.
+    pub async fn wait_ct(&self) {
+        self.wait(|clock| clock.casttime).await
+    }
+
+    async fn wait(&self, end: impl Fn(&Clock) -> u64) {
+        let mut changes = self.clock.subscribe();
+        loop {
+            let remaining = {
+                let clock = changes.borrow_and_update();
+                clock.remaining(end(&clock))
+            };
+            if remaining.is_zero() {
+                return;
+            }
+            // wake early for a new end time, or a new reading of the server's clock; the sender
+            // is held by `self`, so `changed` can't fail
+            let _ = timeout(remaining, changes.changed()).await;
+        }
+    }
+}
+
+fn local_now() -> Duration {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+}
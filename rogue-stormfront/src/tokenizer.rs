@@ -0,0 +1,401 @@
+//! Splits the game stream into tags and text.
+//!
+//! The stream is not XML: text sits between tags with no root element, plenty of tags are never
+//! closed, attributes may be single quoted, and a bare `&` shows up in text. So nothing here
+//! checks nesting or fails on bad markup; anything that can't be a tag is passed on as text. The
+//! only thing held back is input that might still become a tag (or an entity, or a character)
+//! once the next read arrives.
+
+use std::str;
+
+use tokio::io::{AsyncRead, AsyncReadExt};
+
+/// An opening (or self-closing) tag.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Tag {
+    pub name: String,
+    /// in the order they were given, with entities decoded; valueless attributes get `""`
+    pub attrs: Vec<(String, String)>,
+    /// `<tag/>`
+    pub self_closing: bool,
+}
+
+impl Tag {
+    pub fn attr(&self, name: &str) -> Option<&str> {
+        self.attrs
+            .iter()
+            .find(|(n, _)| n == name)
+            .map(|(_, v)| v.as_str())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Token {
+    /// Text with entities decoded. A run of text may come in several pieces.
+    Text(String),
+    Start(Tag),
+    End(String),
+}
+
+/// A tag that hasn't closed after this many bytes is taken to be text that happens to contain a
+/// `<`, rather than held back forever.
+const MAX_TAG: usize = 16 * 1024;
+
+/// The longest entity worth waiting for, `&#x10FFFF;`.
+const MAX_ENTITY: usize = 10;
+
+/// An incremental tokenizer: [`push`](Tokenizer::push) bytes in as they're read, and take tokens
+/// out with [`next_token`](Tokenizer::next_token).
+#[derive(Debug, Default)]
+pub struct Tokenizer {
+    /// decoded input that hasn't been tokenized yet
+    buf: String,
+    /// the start of a UTF-8 sequence that was cut off at the end of the last push
+    partial: Vec<u8>,
+    eof: bool,
+}
+
+enum Markup {
+    Token(Token, usize),
+    /// a comment, processing instruction or doctype, and its length
+    Skip(usize),
+    /// might still become markup with more input
+    Incomplete,
+    /// the `<` is just text
+    Text,
+}
+
+impl Tokenizer {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn push(&mut self, bytes: &[u8]) {
+        self.partial.extend_from_slice(bytes);
+        let mut rest = &self.partial[..];
+        loop {
+            match str::from_utf8(rest) {
+                Ok(s) => {
+                    self.buf.push_str(s);
+                    rest = &[];
+                    break;
+                }
+                Err(e) => {
+                    let (valid, after) = rest.split_at(e.valid_up_to());
+                    self.buf.push_str(str::from_utf8(valid).unwrap());
+                    match e.error_len() {
+                        Some(len) => {
+                            self.buf.push(char::REPLACEMENT_CHARACTER);
+                            rest = &after[len..];
+                        }
+                        // cut off mid-character; the rest comes with the next push
+                        None => {
+                            rest = after;
+                            break;
+                        }
+                    }
+                }
+            }
+        }
+        let len = self.partial.len() - rest.len();
+        self.partial.drain(..len);
+    }
+
+    /// Marks the end of input, so whatever is held back comes out as text.
+    pub fn finish(&mut self) {
+        if !self.partial.is_empty() {
+            self.partial.clear();
+            self.buf.push(char::REPLACEMENT_CHARACTER);
+        }
+        self.eof = true;
+    }
+
+    /// The next token, or `None` if more input is needed (or, after [`finish`], if there's
+    /// nothing left).
+    ///
+    /// [`finish`]: Tokenizer::finish
+    pub fn next_token(&mut self) -> Option<Token> {
+        loop {
+            if self.buf.is_empty() {
+                return None;
+            }
+            if !self.buf.starts_with('<') {
+                return self.text(0);
+            }
+            match markup(&self.buf) {
+                Markup::Token(token, len) => {
+                    self.buf.drain(..len);
+                    return Some(token);
+                }
+                Markup::Skip(len) => {
+                    self.buf.drain(..len);
+                }
+                Markup::Incomplete if !self.eof && self.buf.len() <= MAX_TAG => return None,
+                Markup::Incomplete | Markup::Text => return self.text(1),
+            }
+        }
+    }
+
+    /// Takes text up to the next `<` at or after `from`.
+    fn text(&mut self, from: usize) -> Option<Token> {
+        let mut end = self.buf[from..]
+            .find('<')
+            .map_or(self.buf.len(), |i| from + i);
+        if end == self.buf.len() && !self.eof {
+            end = hold_back_entity(&self.buf[..end]);
+        }
+        if end == 0 {
+            return None;
+        }
+
+        let text = decode(&self.buf[..end]);
+        self.buf.drain(..end);
+        Some(Token::Text(text))
+    }
+}
+
+/// Where to cut text that runs to the end of the input, so as not to split an entity.
+fn hold_back_entity(text: &str) -> usize {
+    match text.rfind('&') {
+        Some(amp)
+            if text.len() - amp <= MAX_ENTITY
+                && text[amp + 1..]
+                    .bytes()
+                    .all(|b| b.is_ascii_alphanumeric() || b == b'#') =>
+        {
+            amp
+        }
+        _ => text.len(),
+    }
+}
+
+fn markup(s: &str) -> Markup {
+    let b = s.as_bytes();
+    match b.get(1) {
+        None => Markup::Incomplete,
+        Some(b'/') => end_tag(s),
+        Some(b'!') => {
+            if b"<!--".starts_with(&b[..b.len().min(4)]) {
+                match s.find("-->") {
+                    Some(i) if s.len() >= 4 => Markup::Skip(i + 3),
+                    _ => Markup::Incomplete,
+                }
+            } else {
+                s.find('>')
+                    .map_or(Markup::Incomplete, |i| Markup::Skip(i + 1))
+            }
+        }
+        Some(b'?') => s
+            .find('>')
+            .map_or(Markup::Incomplete, |i| Markup::Skip(i + 1)),
+        Some(c) if is_name_start(*c) => start_tag(s),
+        Some(_) => Markup::Text,
+    }
+}
+
+fn end_tag(s: &str) -> Markup {
+    let Some(close) = s.find('>') else {
+        return if s[2..].contains(['<', '\n']) {
+            Markup::Text
+        } else {
+            Markup::Incomplete
+        };
+    };
+    let name = s[2..close].trim();
+    if !name.is_empty() && name.bytes().all(is_name) {
+        Markup::Token(Token::End(name.to_owned()), close + 1)
+    } else {
+        Markup::Text
+    }
+}
+
+fn start_tag(s: &str) -> Markup {
+    let b = s.as_bytes();
+    let mut i = 1;
+    let name_end = i + b[i..].iter().take_while(|&&c| is_name(c)).count();
+    let name = s[i..name_end].to_owned();
+    i = name_end;
+
+    let mut attrs = Vec::new();
+    loop {
+        while i < b.len() && b[i].is_ascii_whitespace() {
+            i += 1;
+        }
+        let Some(&c) = b.get(i) else {
+            return Markup::Incomplete;
+        };
+        match c {
+            b'>' => {
+                return Markup::Token(
+                    Token::Start(Tag {
+                        name,
+                        attrs,
+                        self_closing: false,
+                    }),
+                    i + 1,
+                );
+            }
+            b'/' => match b.get(i + 1) {
+                None => return Markup::Incomplete,
+                Some(b'>') => {
+                    return Markup::Token(
+                        Token::Start(Tag {
+                            name,
+                            attrs,
+                            self_closing: true,
+                        }),
+                        i + 2,
+                    );
+                }
+                // a stray slash; skip it
+                Some(_) => i += 1,
+            },
+            // a new tag before this one closed: this one was never a tag
+            b'<' => return Markup::Text,
+            c if is_name_start(c) => {
+                let attr_end = i + b[i..].iter().take_while(|&&c| is_name(c)).count();
+                let attr = s[i..attr_end].to_owned();
+                i = attr_end;
+
+                let mut j = i;
+                while j < b.len() && b[j].is_ascii_whitespace() {
+                    j += 1;
+                }
+                match b.get(j) {
+                    None => return Markup::Incomplete,
+                    Some(b'=') => {
+                        j += 1;
+                        while j < b.len() && b[j].is_ascii_whitespace() {
+                            j += 1;
+                        }
+                        let Some(&q) = b.get(j) else {
+                            return Markup::Incomplete;
+                        };
+                        let (value, end) = if q == b'"' || q == b'\'' {
+                            // like an end tag, a quote that runs into a new line or tag was
+                            // never an attribute value
+                            match b[j + 1..]
+                                .iter()
+                                .position(|&c| matches!(c, b'\n' | b'<') || c == q)
+                            {
+                                Some(len) if b[j + 1 + len] == q => {
+                                    (&s[j + 1..j + 1 + len], j + len + 2)
+                                }
+                                Some(_) => return Markup::Text,
+                                None => return Markup::Incomplete,
+                            }
+                        } else {
+                            let len = b[j..]
+                                .iter()
+                                .take_while(|&&c| !c.is_ascii_whitespace() && c != b'>')
+                                .count();
+                            let mut value = &s[j..j + len];
+                            // `<tag a=b/>`
+                            if value.ends_with('/') && b.get(j + len) == Some(&b'>') {
+                                value = &value[..value.len() - 1];
+                            }
+                            (value, j + value.len())
+                        };
+                        attrs.push((attr, decode(value)));
+                        i = end;
+                    }
+                    // `<a exist>`, with no value
+                    Some(_) => attrs.push((attr, String::new())),
+                }
+            }
+            // any other junk inside a tag is skipped
+            _ => i += 1,
+        }
+    }
+}
+
+fn is_name_start(c: u8) -> bool {
+    c.is_ascii_alphabetic() || c == b'_'
+}
+
+fn is_name(c: u8) -> bool {
+    c.is_ascii_alphanumeric() || matches!(c, b'_' | b'-' | b':' | b'.')
+}
+
+/// Decodes the XML entities, leaving anything that isn't one (like a bare `&`) alone.
+fn decode(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    let mut rest = s;
+    while let Some(amp) = rest.find('&') {
+        out.push_str(&rest[..amp]);
+        rest = &rest[amp..];
+        let entity = rest[1..]
+            .find(';')
+            .filter(|&i| i < MAX_ENTITY)
+            .and_then(|i| Some((entity(&rest[1..i + 1])?, i + 2)));
+        match entity {
+            Some((c, len)) => {
+                out.push(c);
+                rest = &rest[len..];
+            }
+            None => {
+                out.push('&');
+                rest = &rest[1..];
+            }
+        }
+    }
+    out.push_str(rest);
+    out
+}
+
+fn entity(name: &str) -> Option<char> {
+    match name {
+        "lt" => Some('<'),
+        "gt" => Some('>'),
+        "amp" => Some('&'),
+        "quot" => Some('"'),
+        "apos" => Some('\''),
+        _ => {
+            let code = match name.strip_prefix("#x").or_else(|| name.strip_prefix("#X")) {
+                Some(hex) => u32::from_str_radix(hex, 16).ok()?,
+                None => name.strip_prefix('#')?.parse().ok()?,
+            };
+            char::from_u32(code)
+        }
+    }
+}
+
+/// Reads tokens from an async stream.
+pub struct Reader<R> {
+    inner: R,
+    tokenizer: Tokenizer,
+    buf: Box<[u8]>,
+}
+
+impl<R: AsyncRead + Unpin> Reader<R> {
+    pub fn new(inner: R) -> Self {
+        Self {
+            inner,
+            tokenizer: Tokenizer::new(),
+            buf: vec![0; 8 * 1024].into_boxed_slice(),
+        }
+    }
+
+    /// The next token, or `None` once the stream has ended and everything's been read.
+    pub async fn next(&mut self) -> std::io::Result<Option<Token>> {
+        loop {
+            if let Some(token) = self.tokenizer.next_token() {
+                return Ok(Some(token));
+            }
+            if self.tokenizer.eof {
+                return Ok(None);
+            }
+
+            let read = self.inner.read(&mut self.buf).await?;
+            if read == 0 {
+                self.tokenizer.finish();
+            } else {
+                self.tokenizer.push(&self.buf[..read]);
+            }
+        }
+    }
+
+    pub fn into_inner(self) -> R {
+        self.inner
+    }
+}
@@ -0,0 +1,70 @@
+//! Helpers shared by the tests: running input through the tokenizer and parser, and building the
+//! values they expect.
+
+#![allow(dead_code)]
+
+use rogue_stormfront::{
+    Change, Format, GameEvent, GameState, Object, Parser, Span, Token, Tokenizer,
+};
+
+/// Tokenizes `chunks` as if each was one read, then finishes.
+pub fn tokens(chunks: &[&[u8]]) -> Vec<Token> {
+    let mut tokenizer = Tokenizer::new();
+    let mut tokens = Vec::new();
+    for chunk in chunks {
+        tokenizer.push(chunk);
+        tokens.extend(std::iter::from_fn(|| tokenizer.next_token()));
+    }
+    tokenizer.finish();
+    tokens.extend(std::iter::from_fn(|| tokenizer.next_token()));
+
+    // text can come out in pieces; join them up so tests don't depend on where the reads split
+    let mut joined: Vec<Token> = Vec::new();
+    for token in tokens {
+        match (joined.last_mut(), token) {
+            (Some(Token::Text(last)), Token::Text(text)) => last.push_str(&text),
+            (_, token) => joined.push(token),
+        }
+    }
+    joined
+}
+
+pub fn events(input: &str) -> Vec<GameEvent> {
+    let mut parser = Parser::new();
+    for token in tokens(&[input.as_bytes()]) {
+        parser.push(token);
+    }
+    parser.finish();
+    std::iter::from_fn(|| parser.next_event()).collect()
+}
+
+/// Applies the stream to the state, returning every change.
+pub fn apply(state: &mut GameState, input: &str) -> Vec<Change> {
+    events(input)
+        .iter()
+        .flat_map(|event| state.apply(event))
+        .collect()
+}
+
+pub fn text(text: &str) -> GameEvent {
+    GameEvent::Text(text.to_owned())
+}
+
+pub fn object(exist: &str, noun: &str, name: &str) -> Object {
+    Object {
+        exist: Some(exist.to_owned()),
+        noun: Some(noun.to_owned()),
+        name: name.to_owned(),
+    }
+}
+
+pub fn span(text: &str, format: Format) -> Span {
+    Span {
+        text: text.to_owned(),
+        format,
+    }
+}
+
+pub fn plain(text: &str) -> Span {
+    span(text, Format::default())
+}
@@ -0,0 +1,218 @@
+mod common;
+
+use common::events;
+use rogue_stormfront::{DialogEvent, Dialogs, GameEvent, Layout, Widget};
+
+/// Applies the stream to the dialogs, returning what happened.
+fn apply(dialogs: &mut Dialogs, input: &str) -> Vec<DialogEvent> {
+    events(input)
+        .iter()
+        .flat_map(|event| dialogs.apply(event))
+        .collect()
+}
+
+fn updated(dialog: &str, widget: &str) -> DialogEvent {
+    DialogEvent::Updated {
+        dialog: dialog.to_owned(),
+        widget: widget.to_owned(),
+    }
+}
+
+#[test]
+fn widgets() {
+    assert_eq!(
+        events(
+            "<cmdButton id='stand' value='Stand' cmd='stand' echo='stand' width='50'/>\
+             <link id='l1' value='Spells' anchor_top='stand' justify='4'/>\
+             <label id='n' left='10' top='2' height='15' align='n' anchor_left='l1'/>"
+        ),
+        [
+            GameEvent::Widget {
+                id: "stand".to_owned(),
+                widget: Widget::CmdButton {
+                    value: "Stand".to_owned(),
+                    cmd: Some("stand".to_owned()),
+                    echo: Some("stand".to_owned()),
+                },
+                layout: Layout {
+                    width: Some("50".to_owned()),
+                    ..Layout::default()
+                },
+            },
+            GameEvent::Widget {
+                id: "l1".to_owned(),
+                widget: Widget::Link {
+                    value: "Spells".to_owned(),
+                    cmd: None,
+                    echo: None,
+                },
+                layout: Layout {
+                    anchor_top: Some("stand".to_owned()),
+                    justify: Some("4".to_owned()),
+                    ..Layout::default()
+                },
+            },
+            GameEvent::Widget {
+                id: "n".to_owned(),
+                widget: Widget::Label {
+                    value: String::new(),
+                },
+                layout: Layout {
+                    left: Some("10".to_owned()),
+                    top: Some("2".to_owned()),
+                    width: None,
+                    height: Some("15".to_owned()),
+                    align: Some("n".to_owned()),
+                    anchor_left: Some("l1".to_owned()),
+                    anchor_top: None,
+                    justify: None,
+                },
+            },
+        ]
+    );
+}
+
+const INJURIES: &str = "<openDialog type='dynamic' id='injuries' title='Injuries' location='main' width='180' height='200' resident='true'>\
+    <dialogData id='injuries' clear='t'>\
+    <skin id='body' name='body' controls='head' left='0' top='0'/>\
+    <image id='head' name='head1' cmd='look at my head' left='10' top='0'/>\
+    <label id='lblHead' value='Minor cuts' left='30' top='0'/>\
+    </dialogData></openDialog>";
+
+#[test]
+fn open() {
+    let mut dialogs = Dialogs::new();
+    assert_eq!(
+        apply(&mut dialogs, INJURIES),
+        [
+            DialogEvent::Opened("injuries".to_owned()),
+            DialogEvent::Cleared("injuries".to_owned()),
+            updated("injuries", "body"),
+            updated("injuries", "head"),
+            updated("injuries", "lblHead"),
+        ]
+    );
+
+    let injuries = dialogs.get("injuries").unwrap();
+    assert_eq!(injuries.title.as_deref(), Some("Injuries"));
+    assert_eq!(injuries.kind.as_deref(), Some("dynamic"));
+    assert_eq!(injuries.location.as_deref(), Some("main"));
+    assert_eq!(injuries.width.as_deref(), Some("180"));
+    assert_eq!(injuries.height.as_deref(), Some("200"));
+    assert!(injuries.resident);
+    assert_eq!(
+        injuries
+            .widgets
+            .iter()
+            .map(|(id, ..)| id.as_str())
+            .collect::<Vec<_>>(),
+        ["body", "head", "lblHead"]
+    );
+    assert_eq!(
+        injuries.widget("head"),
+        Some(&Widget::Image {
+            name: "head1".to_owned(),
+            cmd: Some("look at my head".to_owned()),
+        })
+    );
+    assert_eq!(
+        injuries.widget("body"),
+        Some(&Widget::Skin {
+            name: "body".to_owned(),
+        })
+    );
+    assert_eq!(
+        injuries.layout("head"),
+        Some(&Layout {
+            left: Some("10".to_owned()),
+            top: Some("0".to_owned()),
+            ..Layout::default()
+        })
+    );
+}
+
+#[test]
+fn update_and_clear() {
+    let mut dialogs = Dialogs::new();
+    let minivitals = "<dialogData id='minivitals'>\
+        <progressBar id='health' value='100' text='health 118/118'/>\
+        <progressBar id='mana' value='100' text='mana 12/12'/>\
+        </dialogData>";
+    assert_eq!(
+        apply(&mut dialogs, minivitals),
+        [
+            updated("minivitals", "health"),
+            updated("minivitals", "mana")
+        ]
+    );
+    // only what changed is raised
+    assert_eq!(
+        apply(
+            &mut dialogs,
+            "<dialogData id='minivitals'>\
+             <progressBar id='health' value='93' text='health 110/118'/>\
+             <progressBar id='mana' value='100' text='mana 12/12'/></dialogData>"
+        ),
+        [updated("minivitals", "health")]
+    );
+    assert_eq!(
+        dialogs.get("minivitals").unwrap().widget("health"),
+        Some(&Widget::ProgressBar {
+            value: 93,
+            text: "health 110/118".to_owned(),
+        })
+    );
+    // so is a move
+    assert_eq!(
+        apply(
+            &mut dialogs,
+            "<dialogData id='minivitals'>\
+             <progressBar id='mana' value='100' text='mana 12/12' left='50%'/></dialogData>"
+        ),
+        [updated("minivitals", "mana")]
+    );
+    assert_eq!(
+        dialogs
+            .get("minivitals")
+            .unwrap()
+            .layout("mana")
+            .unwrap()
+            .left
+            .as_deref(),
+        Some("50%")
+    );
+
+    assert_eq!(
+        apply(
+            &mut dialogs,
+            "<dialogData id='expr' clear='t'></dialogData>\
+             <dialogData id='expr'><link id='l1' value='Gained a rank' cmd='exp'/></dialogData>"
+        ),
+        [
+            DialogEvent::Cleared("expr".to_owned()),
+            updated("expr", "l1")
+        ]
+    );
+
+    // clearing leaves other dialogs alone
+    assert_eq!(
+        apply(&mut dialogs, "<dialogData id='minivitals' clear='t'/>"),
+        [DialogEvent::Cleared("minivitals".to_owned())]
+    );
+    assert!(dialogs.get("minivitals").unwrap().widgets.is_empty());
+    assert_eq!(dialogs.get("expr").unwrap().widgets.len(), 1);
+}
+
+#[test]
+fn outside_dialogs() {
+    let mut dialogs = Dialogs::new();
+    assert_eq!(
+        apply(
+            &mut dialogs,
+            "<progressBar id='health' value='93' text='health 110/118'/>\
+             <label id='x' value='y'/>"
+        ),
+        []
+    );
+    assert!(dialogs.dialogs.is_empty());
+}
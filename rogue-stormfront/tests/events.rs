@@ -0,0 +1,246 @@
+mod common;
+
+use common::{events, text};
+use rogue_stormfront::{Events, GameEvent, Hand, Layout, Tag};
+
+#[test]
+fn prompt_and_timers() {
+    assert_eq!(
+        events(
+            "<roundTime value='1718900003'/><castTime value=\"1718900005\"/>\
+             <prompt time=\"1718900000\">R&gt;</prompt>\r\n"
+        ),
+        [
+            GameEvent::RoundTime(1718900003),
+            GameEvent::CastTime(1718900005),
+            GameEvent::Prompt {
+                time: Some(1718900000),
+                text: "R>".to_owned(),
+            },
+            text("\r\n"),
+        ]
+    );
+}
+
+#[test]
+fn vitals_and_indicators() {
+    assert_eq!(
+        events(
+            "<dialogData id='minivitals'>\
+             <progressBar id='health' value='93' text='health 110/118' left='0%' top='0%' width='25%' height='100%'/>\
+             </dialogData>\
+             <indicator id='IconSTANDING' visible='y'/><indicator id='IconKNEELING' visible='n'/>"
+        ),
+        [
+            GameEvent::DialogData {
+                id: "minivitals".to_owned(),
+                clear: false,
+            },
+            GameEvent::ProgressBar {
+                id: "health".to_owned(),
+                value: 93,
+                text: "health 110/118".to_owned(),
+                layout: Layout {
+                    left: Some("0%".to_owned()),
+                    top: Some("0%".to_owned()),
+                    width: Some("25%".to_owned()),
+                    height: Some("100%".to_owned()),
+                    ..Layout::default()
+                },
+            },
+            GameEvent::DialogDataEnd,
+            GameEvent::Indicator {
+                id: "IconSTANDING".to_owned(),
+                visible: true,
+            },
+            GameEvent::Indicator {
+                id: "IconKNEELING".to_owned(),
+                visible: false,
+            },
+        ]
+    );
+}
+
+#[test]
+fn room() {
+    assert_eq!(
+        events(
+            "<nav rm='7150105'/><streamWindow id='main' title='Story' subtitle=\" - [Town Square]\"/>\
+             <style id='roomName' />[Town Square]\n<style id=''/>\
+             <component id='room objs'>You also see <a exist=\"123\" noun=\"well\">a well</a>.</component>\n\
+             <compass><dir value=\"n\"/><dir value=\"out\"/></compass>"
+        ),
+        [
+            GameEvent::Nav(Some("7150105".to_owned())),
+            GameEvent::StreamWindow {
+                id: "main".to_owned(),
+                title: Some("Story".to_owned()),
+                subtitle: Some(" - [Town Square]".to_owned()),
+            },
+            GameEvent::Style("roomName".to_owned()),
+            text("[Town Square]\n"),
+            GameEvent::Style(String::new()),
+            GameEvent::Component("room objs".to_owned()),
+            text("You also see "),
+            GameEvent::Link {
+                exist: Some("123".to_owned()),
+                noun: Some("well".to_owned()),
+            },
+            text("a well"),
+            GameEvent::LinkEnd,
+            text("."),
+            GameEvent::ComponentEnd,
+            text("\n"),
+            GameEvent::Compass(vec!["n".to_owned(), "out".to_owned()]),
+        ]
+    );
+}
+
+#[test]
+fn hands_and_spell() {
+    assert_eq!(
+        events(
+            "<left>Empty</left><right exist=\"77\" noun=\"broadsword\">a broadsword</right>\
+             <spell exist='spell'>Spirit Warding I</spell>"
+        ),
+        [
+            GameEvent::Left(Hand {
+                exist: None,
+                noun: None,
+                name: "Empty".to_owned(),
+            }),
+            GameEvent::Right(Hand {
+                exist: Some("77".to_owned()),
+                noun: Some("broadsword".to_owned()),
+                name: "a broadsword".to_owned(),
+            }),
+            GameEvent::Spell("Spirit Warding I".to_owned()),
+        ]
+    );
+}
+
+#[test]
+fn streams_and_styles() {
+    assert_eq!(
+        events(
+            "<pushStream id=\"thoughts\"/><preset id='thought'>You hear</preset> hi\n<popStream/>\
+             <clearStream id='inv'/><pushBold/>A goblin<popBold/><output class=\"mono\"/>\
+             <d cmd='look'>LOOK</d><output class=\"\"/>"
+        ),
+        [
+            GameEvent::PushStream("thoughts".to_owned()),
+            GameEvent::Preset("thought".to_owned()),
+            text("You hear"),
+            GameEvent::PresetEnd,
+            text(" hi\n"),
+            GameEvent::PopStream,
+            GameEvent::ClearStream("inv".to_owned()),
+            GameEvent::PushBold,
+            text("A goblin"),
+            GameEvent::PopBold,
+            GameEvent::Output("mono".to_owned()),
+            GameEvent::Command(Some("look".to_owned())),
+            text("LOOK"),
+            GameEvent::CommandEnd,
+            GameEvent::Output(String::new()),
+        ]
+    );
+}
+
+#[test]
+fn setup() {
+    assert_eq!(
+        events(
+            "<mode id=\"GAME\"/><settingsInfo client=\"1.0.1.26\" instance=\"GS4\"/>\
+             <resource picture=\"0\"/>\
+             <openDialog type='dynamic' id='injuries' title='Injuries' location='main' height='200' resident='true'>\
+             <dialogData id='injuries' clear='t'/></openDialog>"
+        ),
+        [
+            GameEvent::Mode("GAME".to_owned()),
+            GameEvent::SettingsInfo {
+                instance: Some("GS4".to_owned()),
+            },
+            GameEvent::Resource("0".to_owned()),
+            GameEvent::OpenDialog {
+                id: "injuries".to_owned(),
+                title: Some("Injuries".to_owned()),
+                kind: Some("dynamic".to_owned()),
+                location: Some("main".to_owned()),
+                width: None,
+                height: Some("200".to_owned()),
+                resident: true,
+            },
+            GameEvent::DialogData {
+                id: "injuries".to_owned(),
+                clear: true,
+            },
+            GameEvent::DialogDataEnd,
+            GameEvent::OpenDialogEnd,
+        ]
+    );
+}
+
+#[test]
+fn unknown() {
+    let tag = Tag {
+        name: "exposeStream".to_owned(),
+        attrs: vec![("id".to_owned(), "combat".to_owned())],
+        self_closing: false,
+    };
+    assert_eq!(
+        events("<exposeStream id='combat'>x</exposeStream><roundTime value='soon'/>"),
+        [
+            GameEvent::Unknown(tag),
+            text("x"),
+            GameEvent::UnknownEnd("exposeStream".to_owned()),
+            GameEvent::Unknown(Tag {
+                name: "roundTime".to_owned(),
+                attrs: vec![("value".to_owned(), "soon".to_owned())],
+                self_closing: true,
+            }),
+            GameEvent::UnknownEnd("roundTime".to_owned()),
+        ]
+    );
+}
+
+#[test]
+fn unclosed() {
+    assert_eq!(
+        events("<prompt time=\"1\">&gt;\r\nYou swing<spell>None<left>Empty"),
+        [
+            GameEvent::Prompt {
+                time: Some(1),
+                text: ">".to_owned(),
+            },
+            text("\r\nYou swing"),
+            GameEvent::Spell("None".to_owned()),
+            GameEvent::Left(Hand {
+                exist: None,
+                noun: None,
+                name: "Empty".to_owned(),
+            }),
+        ]
+    );
+}
+
+#[tokio::test]
+async fn reader() {
+    let stream: &[u8] = b"<prompt time=\"1\">&gt;</prompt><compass><dir value=\"ne\"/>";
+    let mut events = Events::new(stream);
+
+    let mut read = Vec::new();
+    while let Some(event) = events.next().await.unwrap() {
+        read.push(event);
+    }
+    assert_eq!(
+        read,
+        [
+            GameEvent::Prompt {
+                time: Some(1),
+                text: ">".to_owned(),
+            },
+            GameEvent::Compass(vec!["ne".to_owned()]),
+        ]
+    );
+}
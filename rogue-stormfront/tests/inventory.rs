@@ -0,0 +1,109 @@
+mod common;
+
+use common::{apply, object};
+use rogue_stormfront::{Change, Container, GameState, Location};
+
+#[test]
+fn worn() {
+    let mut state = GameState::new();
+    let listing = "<clearStream id='inv' ifClosed=''/><pushStream id='inv'/>Your worn items are:\n\
+        \x20 <a exist=\"10\" noun=\"backpack\">a leather backpack</a>\n\
+        \x20 <a exist=\"11\" noun=\"cloak\">a grey cloak</a>\n<popStream/>\
+        You also see <a exist=\"12\" noun=\"well\">a well</a>.\n";
+    assert_eq!(apply(&mut state, listing), [Change::Worn]);
+    assert_eq!(
+        state.inventory.worn,
+        [
+            object("10", "backpack", "a leather backpack"),
+            object("11", "cloak", "a grey cloak"),
+        ]
+    );
+    assert_eq!(apply(&mut state, listing), []);
+
+    assert_eq!(
+        apply(
+            &mut state,
+            "<clearStream id='inv'/><pushStream id='inv'/>Your worn items are:\n\
+             \x20 <a exist=\"10\" noun=\"backpack\">a leather backpack</a>\n<popStream/>"
+        ),
+        [Change::Worn]
+    );
+    assert_eq!(state.inventory.worn.len(), 1);
+}
+
+#[test]
+fn containers() {
+    let mut state = GameState::new();
+    assert_eq!(
+        apply(
+            &mut state,
+            "<container id='stow' title=\"My Backpack\" target='#10' location='right' save='' resident='true'/>\
+             <clearContainer id=\"stow\"/>\
+             <inv id='stow'>In the <a exist=\"10\" noun=\"backpack\">backpack</a>:</inv>\
+             <inv id='stow'> <a exist=\"20\" noun=\"gem\">a blue gem</a></inv>\
+             <inv id='stow'> <a exist=\"21\" noun=\"coin\">a silver coin</a></inv>\
+             <exposeContainer id='stow'/>"
+        ),
+        // the container, two items and being exposed
+        [
+            Change::Container("stow".to_owned()),
+            Change::Container("stow".to_owned()),
+            Change::Container("stow".to_owned()),
+            Change::Container("stow".to_owned()),
+        ]
+    );
+    assert_eq!(
+        state.inventory.container("stow"),
+        Some(&Container {
+            title: Some("My Backpack".to_owned()),
+            exist: Some("10".to_owned()),
+            exposed: true,
+            contents: vec![
+                object("20", "gem", "a blue gem"),
+                object("21", "coin", "a silver coin"),
+            ],
+        })
+    );
+
+    // the game sends the container again after anything goes in or out
+    assert_eq!(
+        apply(
+            &mut state,
+            "<clearContainer id=\"stow\"/><inv id='stow'> <a exist=\"21\" noun=\"coin\">a silver coin</a></inv>"
+        ),
+        [
+            Change::Container("stow".to_owned()),
+            Change::Container("stow".to_owned()),
+        ]
+    );
+    assert_eq!(
+        state.inventory.container("stow").unwrap().contents,
+        [object("21", "coin", "a silver coin")]
+    );
+}
+
+#[test]
+fn queries() {
+    let mut state = GameState::new();
+    apply(
+        &mut state,
+        "<right exist=\"30\" noun=\"sword\">a broadsword</right><left>Empty</left>\
+         <clearStream id='inv'/><pushStream id='inv'/>\
+         <a exist=\"10\" noun=\"backpack\">a leather backpack</a>\n<popStream/>\
+         <container id='stow' target='#10'/>\
+         <inv id='stow'> <a exist=\"20\" noun=\"gem\">a blue gem</a></inv>\
+         <inv id='stow'> <a exist=\"31\" noun=\"sword\">a short sword</a></inv>",
+    );
+    let inventory = &state.inventory;
+
+    assert_eq!(inventory.locate("20"), Some(Location::Container("stow")));
+    assert_eq!(inventory.locate("10"), Some(Location::Worn));
+    assert_eq!(inventory.locate("99"), None);
+    assert_eq!(inventory.find_noun("sword"), Some(Location::Right));
+    assert_eq!(
+        inventory.find_noun("gem"),
+        Some(Location::Container("stow"))
+    );
+    assert_eq!(inventory.find_noun("Empty"), None);
+    assert_eq!(inventory.items().count(), 3);
+}
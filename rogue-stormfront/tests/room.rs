@@ -0,0 +1,83 @@
+mod common;
+
+use common::{apply, object};
+use rogue_stormfront::{Change, GameState, Room};
+
+const GS4: &str = "<nav/><streamWindow id='main' title='Story' subtitle=\" - [Wehnimer's, Town Square Central]\"/>\
+    <style id='roomName' />[Wehnimer's, Town Square Central] (u7150105)\n<style id=''/>\
+    <preset id='roomDesc'><component id='room desc'>This is the heart of the town.</component></preset>\
+    <component id='room objs'>You also see <a exist=\"123\" noun=\"well\">a well</a> and \
+    <pushBold/><a exist=\"456\" noun=\"rat\">a giant rat</a><popBold/>.</component>\n\
+    <component id='room players'>Also here: Lady <a exist=\"-789\" noun=\"Mira\">Mira</a>.</component>\n\
+    <component id='room exits'>Obvious paths: north, east, out\n</component>\n\
+    <compass><dir value=\"n\"/><dir value=\"e\"/><dir value=\"out\"/></compass>";
+
+#[test]
+fn gemstone() {
+    let mut state = GameState::new();
+    assert_eq!(
+        apply(&mut state, GS4),
+        [
+            Change::RoomId,
+            Change::RoomName,
+            Change::RoomDescription,
+            Change::RoomObjects,
+            Change::RoomPlayers,
+            Change::RoomPaths,
+            Change::RoomExits,
+        ]
+    );
+    assert_eq!(
+        state.room,
+        Room {
+            id: Some("7150105".to_owned()),
+            name: "[Wehnimer's, Town Square Central]".to_owned(),
+            description: "This is the heart of the town.".to_owned(),
+            objects: vec![
+                object("123", "well", "a well"),
+                object("456", "rat", "a giant rat"),
+            ],
+            players: vec![object("-789", "Mira", "Mira")],
+            exits: vec!["n".to_owned(), "e".to_owned(), "out".to_owned()],
+            paths: vec!["north".to_owned(), "east".to_owned(), "out".to_owned()],
+            extra: String::new(),
+        }
+    );
+
+    // the same room again changes nothing
+    assert_eq!(apply(&mut state, GS4), []);
+
+    // a room without a number
+    assert_eq!(
+        apply(
+            &mut state,
+            "<nav/><style id='roomName' />[North Gate]\n<style id=''/>\
+             <component id='room objs'></component><compass></compass>"
+        ),
+        [
+            Change::RoomId,
+            Change::RoomName,
+            Change::RoomObjects,
+            Change::RoomExits,
+        ]
+    );
+    assert_eq!(state.room.id, None);
+    assert_eq!(state.room.name, "[North Gate]");
+    assert!(state.room.objects.is_empty());
+}
+
+#[test]
+fn dragonrealms() {
+    let mut state = GameState::new();
+    apply(
+        &mut state,
+        "<nav rm='1234'/><style id=\"roomName\" />[The Crossing, Hodierna Way]\n<style id=\"\"/>\
+         <component id='room extra'>A sign hangs here.</component>\
+         <component id='room exits'>Obvious paths: <d>north</d>, <d>east</d>, and <d>west</d>.\
+         <compass></compass></component>",
+    );
+    assert_eq!(state.room.id.as_deref(), Some("1234"));
+    assert_eq!(state.room.name, "[The Crossing, Hodierna Way]");
+    assert_eq!(state.room.extra, "A sign hangs here.");
+    assert_eq!(state.room.paths, ["north", "east", "west"]);
+}
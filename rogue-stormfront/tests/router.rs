@@ -0,0 +1,97 @@
+mod common;
+
+use common::{events, text};
+use rogue_stormfront::{GameEvent, Router, Window};
+use tokio::sync::mpsc::UnboundedReceiver;
+
+fn route(router: &mut Router, input: &str) {
+    for event in events(input) {
+        router.route(event);
+    }
+}
+
+fn received(rx: &mut UnboundedReceiver<GameEvent>) -> Vec<GameEvent> {
+    std::iter::from_fn(|| rx.try_recv().ok()).collect()
+}
+
+const STREAM: &str = "You swing.\n\
+    <pushStream id=\"thoughts\"/>[General] Mira: \"hi\"\n<popStream/>\
+    <pushStream id='logons'/> * Mira joins the adventure.\n<popStream/>\
+    Done.\n";
+
+#[test]
+fn split() {
+    let mut router = Router::new();
+    let mut main = router.subscribe("main");
+    let mut thoughts = router.subscribe("thoughts");
+    route(&mut router, STREAM);
+
+    assert_eq!(received(&mut main), [text("You swing.\n"), text("Done.\n")]);
+    assert_eq!(received(&mut thoughts), [text("[General] Mira: \"hi\"\n")]);
+}
+
+#[test]
+fn fallback() {
+    let mut router = Router::new()
+        .fallback("thoughts", "main")
+        .fallback("logons", "thoughts");
+    let mut main = router.subscribe("main");
+    route(&mut router, STREAM);
+    assert_eq!(
+        received(&mut main),
+        [
+            text("You swing.\n"),
+            text("[General] Mira: \"hi\"\n"),
+            text(" * Mira joins the adventure.\n"),
+            text("Done.\n"),
+        ]
+    );
+
+    // unsubscribing falls back too
+    let thoughts = router.subscribe("thoughts");
+    drop(thoughts);
+    route(
+        &mut router,
+        "<pushStream id=\"thoughts\"/>again\n<popStream/>",
+    );
+    assert_eq!(received(&mut main), [text("again\n")]);
+}
+
+#[test]
+fn circular_fallback() {
+    let mut router = Router::new().fallback("a", "b").fallback("b", "a");
+    let mut main = router.subscribe("main");
+    route(&mut router, "<pushStream id='a'/>lost\n<popStream/>kept\n");
+    assert_eq!(received(&mut main), [text("kept\n")]);
+}
+
+#[test]
+fn clear_and_windows() {
+    let mut router = Router::new();
+    let mut inv = router.subscribe("inv");
+    route(
+        &mut router,
+        "<streamWindow id='inv' title='My Inventory' target='wear' ifClosed=''/>\
+         <streamWindow id='main' title='Story' subtitle=\" - [Town Square]\"/>\
+         <streamWindow id='main' subtitle=\" - [North Gate]\"/>\
+         <clearStream id='inv'/><pushStream id='inv'/>a pack\n<popStream/>",
+    );
+
+    assert_eq!(
+        received(&mut inv),
+        [GameEvent::ClearStream("inv".to_owned()), text("a pack\n")]
+    );
+    assert_eq!(
+        router.window("main"),
+        Some(&Window {
+            title: Some("Story".to_owned()),
+            subtitle: Some(" - [North Gate]".to_owned()),
+        })
+    );
+    assert_eq!(
+        router.window("inv").and_then(|w| w.title.as_deref()),
+        Some("My Inventory")
+    );
+    assert_eq!(router.windows().count(), 2);
+    assert_eq!(router.current(), "main");
+}
@@ -0,0 +1,123 @@
+mod common;
+
+use common::{events, plain, span};
+use rogue_stormfront::{Format, Line, Lines, Link};
+
+fn lines(input: &str) -> Vec<Line> {
+    let mut lines = Lines::new();
+    for event in events(input) {
+        lines.push(&event);
+    }
+    lines.flush();
+    std::iter::from_fn(|| lines.next_line()).collect()
+}
+
+#[test]
+fn bold_and_links() {
+    assert_eq!(
+        lines(
+            "You also see <pushBold/><a exist=\"456\" noun=\"rat\">a giant rat</a><popBold/> and \
+             <a exist=\"123\" noun=\"well\">a well</a>.\r\n"
+        ),
+        [Line {
+            spans: vec![
+                plain("You also see "),
+                span(
+                    "a giant rat",
+                    Format {
+                        bold: true,
+                        link: Some(Link::Object {
+                            exist: Some("456".to_owned()),
+                            noun: Some("rat".to_owned()),
+                        }),
+                        ..Format::default()
+                    }
+                ),
+                plain(" and "),
+                span(
+                    "a well",
+                    Format {
+                        link: Some(Link::Object {
+                            exist: Some("123".to_owned()),
+                            noun: Some("well".to_owned()),
+                        }),
+                        ..Format::default()
+                    }
+                ),
+                plain("."),
+            ],
+        }]
+    );
+}
+
+#[test]
+fn presets_and_styles() {
+    let lines = lines(
+        "<style id='roomName'/>[Town Square]\n<style id=''/>\
+         <preset id='speech'>You say</preset>, \"Hi.\"\n\
+         <output class=\"mono\"/>  Name  Level\n<output class=\"\"/>",
+    );
+    assert_eq!(
+        lines,
+        [
+            Line {
+                spans: vec![span(
+                    "[Town Square]",
+                    Format {
+                        style: Some("roomName".to_owned()),
+                        ..Format::default()
+                    }
+                )],
+            },
+            Line {
+                spans: vec![
+                    span(
+                        "You say",
+                        Format {
+                            preset: Some("speech".to_owned()),
+                            ..Format::default()
+                        }
+                    ),
+                    plain(", \"Hi.\""),
+                ],
+            },
+            Line {
+                spans: vec![span(
+                    "  Name  Level",
+                    Format {
+                        mono: true,
+                        ..Format::default()
+                    }
+                )],
+            },
+        ]
+    );
+    assert_eq!(lines[1].text(), "You say, \"Hi.\"");
+}
+
+#[test]
+fn commands() {
+    let command = |cmd: &str| Format {
+        link: Some(Link::Command(cmd.to_owned())),
+        ..Format::default()
+    };
+    assert_eq!(
+        lines("Try <d cmd='look at sign'>the sign</d> or <d>LOOK</d><d>HELP</d>.\n\nEnd"),
+        [
+            Line {
+                spans: vec![
+                    plain("Try "),
+                    span("the sign", command("look at sign")),
+                    plain(" or "),
+                    span("LOOK", command("LOOK")),
+                    span("HELP", command("HELP")),
+                    plain("."),
+                ],
+            },
+            Line::default(),
+            Line {
+                spans: vec![plain("End")],
+            },
+        ]
+    );
+}
@@ -0,0 +1,88 @@
+mod common;
+
+use common::apply;
+use rogue_stormfront::{Change, GameState, Hand, Vital};
+
+#[test]
+fn vitals_and_stance() {
+    let mut state = GameState::new();
+    assert_eq!(
+        apply(
+            &mut state,
+            "<dialogData id='minivitals'>\
+             <progressBar id='health' value='93' text='health 110/118'/>\
+             <progressBar id='mana' value='100' text='mana 12/12'/>\
+             </dialogData>\
+             <dialogData id='stance'><progressBar id='pbarStance' value='80' text='guarded (80%)'/></dialogData>"
+        ),
+        [
+            Change::Vital("health".to_owned()),
+            Change::Vital("mana".to_owned()),
+            Change::Stance,
+            Change::Vital("pbarStance".to_owned()),
+        ]
+    );
+    assert_eq!(
+        state.vitals["health"],
+        Vital {
+            value: 93,
+            text: "health 110/118".to_owned(),
+            current: Some(110),
+            max: Some(118),
+        }
+    );
+    assert_eq!(state.stance.as_deref(), Some("guarded"));
+
+    assert_eq!(
+        apply(
+            &mut state,
+            "<progressBar id='health' value='93' text='health 110/118'/>\
+             <progressBar id='mana' value='91' text='mana 11/12'/>"
+        ),
+        [Change::Vital("mana".to_owned())]
+    );
+    assert_eq!(state.vitals["mana"].current, Some(11));
+}
+
+#[test]
+fn hands_spell_and_indicators() {
+    let mut state = GameState::new();
+    assert_eq!(
+        apply(
+            &mut state,
+            "<left>Empty</left><right exist=\"77\" noun=\"broadsword\">a broadsword</right>\
+             <spell>None</spell><indicator id='IconSTANDING' visible='y'/>\
+             <indicator id='IconHIDDEN' visible='n'/>"
+        ),
+        [
+            Change::Left,
+            Change::Right,
+            Change::Spell,
+            Change::Indicator("IconSTANDING".to_owned()),
+        ]
+    );
+    assert!(state.inventory.left.is_empty());
+    assert_eq!(
+        state.inventory.right,
+        Hand {
+            exist: Some("77".to_owned()),
+            noun: Some("broadsword".to_owned()),
+            name: "a broadsword".to_owned(),
+        }
+    );
+    assert_eq!(state.spell, "None");
+    assert!(state.indicators.contains("IconSTANDING"));
+
+    assert_eq!(
+        apply(
+            &mut state,
+            "<indicator id='IconSTANDING' visible='n'/><indicator id='IconKNEELING' visible='y'/>\
+             <left>Empty</left>"
+        ),
+        [
+            Change::Indicator("IconSTANDING".to_owned()),
+            Change::Indicator("IconKNEELING".to_owned()),
+        ]
+    );
+    assert!(!state.indicators.contains("IconSTANDING"));
+}
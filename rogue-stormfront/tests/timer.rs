@@ -0,0 +1,72 @@
+use std::time::Duration;
+
+use rogue_stormfront::{GameEvent, Timers};
+use tokio::time::{Instant, advance};
+
+fn prompt(time: u64) -> GameEvent {
+    GameEvent::Prompt {
+        time: Some(time),
+        text: ">".to_owned(),
+    }
+}
+
+#[tokio::test(start_paused = true)]
+async fn remaining() {
+    let timers = Timers::new();
+    timers.apply(&GameEvent::RoundTime(1000));
+    timers.apply(&GameEvent::CastTime(1002));
+    // the server's clock is far behind ours; only it counts
+    timers.apply(&prompt(995));
+
+    assert_eq!(timers.server_now(), Duration::from_secs(995));
+    assert!(timers.offset() < -1e9);
+    assert_eq!(timers.rt_remaining(), Duration::from_secs(5));
+    assert_eq!(timers.ct_remaining(), Duration::from_secs(7));
+
+    advance(Duration::from_millis(2500)).await;
+    assert_eq!(timers.rt_remaining(), Duration::from_millis(2500));
+
+    advance(Duration::from_secs(3)).await;
+    assert_eq!(timers.rt_remaining(), Duration::ZERO);
+    assert_eq!(timers.ct_remaining(), Duration::from_millis(1500));
+
+    // a later prompt resynchronizes
+    timers.apply(&prompt(1001));
+    assert_eq!(timers.ct_remaining(), Duration::from_secs(1));
+}
+
+#[tokio::test(start_paused = true)]
+async fn wait_rt() {
+    let timers = Timers::new();
+    timers.apply(&prompt(100));
+    timers.apply(&GameEvent::RoundTime(103));
+
+    let start = Instant::now();
+    timers.wait_rt().await;
+    assert_eq!(start.elapsed(), Duration::from_secs(3));
+
+    // nothing to wait for
+    timers.wait_rt().await;
+    timers.wait_ct().await;
+    assert_eq!(start.elapsed(), Duration::from_secs(3));
+}
+
+#[tokio::test(start_paused = true)]
+async fn wait_rt_extended() {
+    let timers = Timers::new();
+    timers.apply(&prompt(100));
+    timers.apply(&GameEvent::RoundTime(102));
+
+    let start = Instant::now();
+    let waiter = tokio::spawn({
+        let timers = timers.clone();
+        async move { timers.wait_rt().await }
+    });
+
+    tokio::time::sleep(Duration::from_secs(1)).await;
+    timers.apply(&prompt(101));
+    timers.apply(&GameEvent::RoundTime(106));
+
+    waiter.await.unwrap();
+    assert_eq!(start.elapsed(), Duration::from_secs(6));
+}
@@ -0,0 +1,235 @@
+mod common;
+
+use common::tokens;
+use rogue_stormfront::{Reader, Tag, Token, Tokenizer};
+
+fn text(text: &str) -> Token {
+    Token::Text(text.to_owned())
+}
+
+fn start(name: &str, attrs: &[(&str, &str)], self_closing: bool) -> Token {
+    Token::Start(Tag {
+        name: name.to_owned(),
+        attrs: attrs
+            .iter()
+            .map(|(n, v)| (n.to_string(), v.to_string()))
+            .collect(),
+        self_closing,
+    })
+}
+
+fn end(name: &str) -> Token {
+    Token::End(name.to_owned())
+}
+
+#[test]
+fn prompt() {
+    assert_eq!(
+        tokens(&[b"<prompt time=\"1718900000\">&gt;</prompt>\n"]),
+        [
+            start("prompt", &[("time", "1718900000")], false),
+            text(">"),
+            end("prompt"),
+            text("\n"),
+        ]
+    );
+}
+
+#[test]
+fn split_anywhere() {
+    let input = b"<pushStream id='inv'/>Your pack:<a exist=\"123\" noun=\"pack\">a pack</a>\r\n<popStream/>";
+    let whole = tokens(&[input]);
+    assert_eq!(
+        whole,
+        [
+            start("pushStream", &[("id", "inv")], true),
+            text("Your pack:"),
+            start("a", &[("exist", "123"), ("noun", "pack")], false),
+            text("a pack"),
+            end("a"),
+            text("\r\n"),
+            start("popStream", &[], true),
+        ]
+    );
+
+    for at in 1..input.len() {
+        let (a, b) = input.split_at(at);
+        assert_eq!(tokens(&[a, b]), whole, "split at {at}");
+    }
+    let bytes: Vec<&[u8]> = input.chunks(1).collect();
+    assert_eq!(tokens(&bytes), whole);
+}
+
+#[test]
+fn held_until_complete() {
+    let mut tokenizer = Tokenizer::new();
+    tokenizer.push(b"You see <a exist=\"4\" no");
+    assert_eq!(tokenizer.next_token(), Some(text("You see ")));
+    assert_eq!(tokenizer.next_token(), None);
+
+    tokenizer.push(b"un=\"door\">door</a>");
+    assert_eq!(
+        tokenizer.next_token(),
+        Some(start("a", &[("exist", "4"), ("noun", "door")], false))
+    );
+}
+
+#[test]
+fn unclosed_tags() {
+    assert_eq!(
+        tokens(&[b"<prompt time=\"1\">&gt;<pushBold/>A goblin<popBold/><output class=\"mono\"/>"]),
+        [
+            start("prompt", &[("time", "1")], false),
+            text(">"),
+            start("pushBold", &[], true),
+            text("A goblin"),
+            start("popBold", &[], true),
+            start("output", &[("class", "mono")], true),
+        ]
+    );
+}
+
+#[test]
+fn attributes() {
+    assert_eq!(
+        tokens(&[
+            b"<a exist noun=door title='say \"hi\" > there' n=5 x = \"&lt;&amp;&#65;&#x42;\"/>"
+        ]),
+        [start(
+            "a",
+            &[
+                ("exist", ""),
+                ("noun", "door"),
+                ("title", "say \"hi\" > there"),
+                ("n", "5"),
+                ("x", "<&AB"),
+            ],
+            true
+        )]
+    );
+    assert_eq!(
+        tokens(&[b"<d cmd=look/>"]),
+        [start("d", &[("cmd", "look")], true)]
+    );
+}
+
+#[test]
+fn entities() {
+    assert_eq!(
+        tokens(&[b"fish & chips &amp; &bogus; &#9731; &"]),
+        [text("fish & chips & &bogus; \u{2603} &")]
+    );
+    assert_eq!(tokens(&[b"a &a", b"mp; b"]), [text("a & b")]);
+    assert_eq!(tokens(&[b"a &#x26", b"; b"]), [text("a & b")]);
+}
+
+#[test]
+fn utf8_across_reads() {
+    let input = "Ærindel says, \u{201c}hi\u{201d}".as_bytes();
+    for at in 1..input.len() {
+        let (a, b) = input.split_at(at);
+        assert_eq!(
+            tokens(&[a, b]),
+            [text("Ærindel says, \u{201c}hi\u{201d}")],
+            "split at {at}"
+        );
+    }
+    assert_eq!(tokens(&[b"bad \xff byte"]), [text("bad \u{fffd} byte")]);
+    assert_eq!(tokens(&[b"cut \xe2\x80"]), [text("cut \u{fffd}")]);
+}
+
+#[test]
+fn stray_angle_brackets() {
+    assert_eq!(
+        tokens(&[b"1 < 2 <3 <<b>x</ b>"]),
+        [
+            text("1 < 2 <3 <"),
+            start("b", &[], false),
+            text("x"),
+            end("b"),
+        ]
+    );
+    assert_eq!(
+        tokens(&[b"<a href=\"x\n<b>"]),
+        [text("<a href=\"x\n"), start("b", &[], false)]
+    );
+    assert_eq!(tokens(&[b"trailing <"]), [text("trailing <")]);
+    assert_eq!(tokens(&[b"</ not a tag>"]), [text("</ not a tag>")]);
+}
+
+#[test]
+fn unclosed_quotes_do_not_swallow_the_stream() {
+    // without finish(), as on a live connection: the tag after the broken one still comes out
+    let mut tokenizer = Tokenizer::new();
+    tokenizer.push(b"<a href=\"x\n<b>y</b>");
+    let tokens: Vec<_> = std::iter::from_fn(|| tokenizer.next_token()).collect();
+    assert_eq!(
+        tokens,
+        [
+            text("<a href=\"x\n"),
+            start("b", &[], false),
+            text("y"),
+            end("b")
+        ]
+    );
+
+    let mut tokenizer = Tokenizer::new();
+    tokenizer.push(b"<a title='x<b>");
+    assert_eq!(tokenizer.next_token(), Some(text("<a title='x")));
+    assert_eq!(tokenizer.next_token(), Some(start("b", &[], false)));
+}
+
+#[test]
+fn skipped() {
+    assert_eq!(
+        tokens(&[
+            b"<?xml version='1.0'?><!-- a <b> comment -->x<!",
+            b"DOCTYPE y>z"
+        ]),
+        [text("xz")]
+    );
+}
+
+#[test]
+fn runaway_tag() {
+    let mut input = b"<a title=\"".to_vec();
+    input.extend(std::iter::repeat_n(b'x', 20 * 1024));
+
+    let mut tokenizer = Tokenizer::new();
+    tokenizer.push(&input);
+    let Some(Token::Text(text)) = tokenizer.next_token() else {
+        panic!("expected text");
+    };
+    assert!(text.starts_with("<a title=\"xxx"));
+}
+
+#[tokio::test]
+async fn reader() {
+    let (mut tx, rx) = tokio::io::duplex(8);
+    let writer = tokio::spawn(async move {
+        use tokio::io::AsyncWriteExt;
+        tx.write_all(b"<roundTime value='1718900003'/>You swing\n")
+            .await
+            .unwrap();
+    });
+
+    let mut reader = Reader::new(rx);
+    let mut read = Vec::new();
+    while let Some(token) = reader.next().await.unwrap() {
+        read.push(token);
+    }
+    writer.await.unwrap();
+
+    assert_eq!(
+        read[0],
+        start("roundTime", &[("value", "1718900003")], true)
+    );
+    let text: String = read[1..]
+        .iter()
+        .map(|token| match token {
+            Token::Text(text) => text.as_str(),
+            other => panic!("unexpected {other:?}"),
+        })
+        .collect();
+    assert_eq!(text, "You swing\n");
+}
@@ -0,0 +1,190 @@
+//! An encrypted store for play.net credentials.
+//!
+//! The vault is a JSON document encrypted with XChaCha20-Poly1305, keyed by an Argon2id hash of
+//! the passphrase. On disk it is laid out as:
+//!
+//! ```text
+//! MAGIC | salt (16 bytes) | nonce (24 bytes) | ciphertext
+//! ```
+
+use std::{
+    fmt,
+    fs::{self, OpenOptions},
+    io::{self, Write},
+    path::{Path, PathBuf},
+};
+
+use argon2::Argon2;
+use chacha20poly1305::{
+    AeadCore, KeyInit, XChaCha20Poly1305, XNonce,
+    aead::{Aead, OsRng, rand_core::RngCore},
+};
+use serde::{Deserialize, Serialize};
+use zeroize::{Zeroize, ZeroizeOnDrop, Zeroizing};
+
+const MAGIC: &[u8] = b"ROGUEVAULT1\n";
+const SALT_LEN: usize = 16;
+const NONCE_LEN: usize = 24;
+
+#[derive(thiserror::Error, Debug)]
+pub enum Error {
+    #[error(transparent)]
+    Io(#[from] io::Error),
+    #[error("not a vault file")]
+    Format,
+    #[error("wrong passphrase, or the vault is corrupted")]
+    Decrypt,
+    #[error("failed to encrypt the vault")]
+    Encrypt,
+    #[error("failed to derive a key from the passphrase: {0}")]
+    Kdf(argon2::Error),
+    #[error(transparent)]
+    Json(#[from] serde_json::Error),
+}
+
+/// One play.net account.
+#[derive(Clone, Serialize, Deserialize, Zeroize, ZeroizeOnDrop)]
+pub struct Account {
+    pub account: String,
+    pub password: String,
+    /// game code to use when none is given, e.g. GS3
+    #[serde(default)]
+    pub default_game: Option<String>,
+    /// character name to use when none is given
+    #[serde(default)]
+    pub default_character: Option<String>,
+}
+
+impl fmt::Debug for Account {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Account")
+            .field("account", &self.account)
+            .field("password", &"<redacted>")
+            .field("default_game", &self.default_game)
+            .field("default_character", &self.default_character)
+            .finish()
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Vault {
+    pub accounts: Vec<Account>,
+}
+
+impl Vault {
+    /// `$CONFIG_DIR/rogue/vault`, e.g. `~/.config/rogue/vault` on Linux.
+    pub fn default_path() -> Option<PathBuf> {
+        dirs::config_dir().map(|dir| dir.join("rogue").join("vault"))
+    }
+
+    pub fn open(path: impl AsRef<Path>, passphrase: &str) -> Result<Self, Error> {
+        Self::decrypt(&fs::read(path)?, passphrase)
+    }
+
+    /// Encrypts the vault with a fresh salt and nonce, and writes it to `path`.
+    ///
+    /// The vault is written to a temporary file next to `path`, readable only by its owner, and
+    /// renamed over it once it's on disk, so a failed save leaves the old vault as it was.
+    pub fn save(&self, path: impl AsRef<Path>, passphrase: &str) -> Result<(), Error> {
+        let path = path.as_ref();
+        let data = self.encrypt(passphrase)?;
+        let dir = match path.parent() {
+            Some(parent) if !parent.as_os_str().is_empty() => parent,
+            _ => Path::new("."),
+        };
+        fs::create_dir_all(dir)?;
+
+        let mut name = path
+            .file_name()
+            .ok_or(io::Error::from(io::ErrorKind::InvalidInput))?
+            .to_owned();
+        // random, so a temp file left behind by a crash can't get in the way
+        name.push(format!(".{:016x}.tmp", OsRng.next_u64()));
+        let tmp = dir.join(name);
+        let written = write_new(&tmp, &data).and_then(|()| fs::rename(&tmp, path));
+        if let Err(err) = written {
+            let _ = fs::remove_file(&tmp);
+            return Err(err.into());
+        }
+
+        // make the rename itself durable; not every platform can open a directory
+        #[cfg(unix)]
+        fs::File::open(dir)?.sync_all()?;
+        Ok(())
+    }
+
+    pub fn decrypt(data: &[u8], passphrase: &str) -> Result<Self, Error> {
+        let data = data.strip_prefix(MAGIC).ok_or(Error::Format)?;
+        if data.len() < SALT_LEN + NONCE_LEN {
+            return Err(Error::Format);
+        }
+        let (salt, data) = data.split_at(SALT_LEN);
+        let (nonce, ciphertext) = data.split_at(NONCE_LEN);
+
+        let plaintext = Zeroizing::new(
+            cipher(passphrase, salt)?
+                .decrypt(XNonce::from_slice(nonce), ciphertext)
+                .map_err(|_| Error::Decrypt)?,
+        );
+        Ok(serde_json::from_slice(&plaintext)?)
+    }
+
+    pub fn encrypt(&self, passphrase: &str) -> Result<Vec<u8>, Error> {
+        let mut salt = [0; SALT_LEN];
+        OsRng.fill_bytes(&mut salt);
+        let nonce = XChaCha20Poly1305::generate_nonce(&mut OsRng);
+
+        let plaintext = Zeroizing::new(serde_json::to_vec(self)?);
+        let ciphertext = cipher(passphrase, &salt)?
+            .encrypt(&nonce, plaintext.as_slice())
+            .map_err(|_| Error::Encrypt)?;
+
+        Ok([MAGIC, &salt, &nonce, &ciphertext].concat())
+    }
+
+    /// Looks up an account by name, ignoring case the way play.net does.
+    pub fn account(&self, account: &str) -> Option<&Account> {
+        self.accounts
+            .iter()
+            .find(|a| a.account.eq_ignore_ascii_case(account))
+    }
+
+    /// Adds `account`, replacing any existing entry with the same name.
+    pub fn insert(&mut self, account: Account) {
+        self.remove(&account.account);
+        self.accounts.push(account);
+    }
+
+    /// Removes an account, returning whether it was there.
+    pub fn remove(&mut self, account: &str) -> bool {
+        let len = self.accounts.len();
+        self.accounts
+            .retain(|a| !a.account.eq_ignore_ascii_case(account));
+        self.accounts.len() != len
+    }
+}
+
+fn cipher(passphrase: &str, salt: &[u8]) -> Result<XChaCha20Poly1305, Error> {
+    let mut key = Zeroizing::new([0; 32]);
+    Argon2::default()
+        .hash_password_into(passphrase.as_bytes(), salt, key.as_mut())
+        .map_err(Error::Kdf)?;
+    Ok(XChaCha20Poly1305::new(key.as_ref().into()))
+}
+
+/// Writes `data` to a new file that only its owner can read, and flushes it to disk.
+fn write_new(path: &Path, data: &[u8]) -> io::Result<()> {
+    let mut options = OpenOptions::new();
+    options.write(true).create_new(true);
+    #[cfg(unix)]
+    std::os::unix::fs::OpenOptionsExt::mode(&mut options, 0o600);
+
+    let mut file = options.open(path)?;
+    file.write_all(data)?;
+    file.sync_all()
+}
+
+/// Prompts for a secret on the terminal without echoing it.
+pub fn prompt_secret(message: &str) -> io::Result<String> {
+    rpassword::prompt_password(message)
+}
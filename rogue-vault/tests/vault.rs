@@ -0,0 +1,85 @@
+use rogue_vault::{Account, Error, Vault};
+
+fn vault() -> Vault {
+    let mut vault = Vault::default();
+    vault.insert(Account {
+        account: "ACCOUNT".into(),
+        password: "hunter2".into(),
+        default_game: Some("GS3".into()),
+        default_character: Some("Foo".into()),
+    });
+    vault
+}
+
+#[test]
+fn round_trips() {
+    let encrypted = vault().encrypt("correct horse").unwrap();
+    let decrypted = Vault::decrypt(&encrypted, "correct horse").unwrap();
+
+    let account = decrypted.account("account").unwrap();
+    assert_eq!(account.password, "hunter2");
+    assert_eq!(account.default_game.as_deref(), Some("GS3"));
+}
+
+#[test]
+fn wrong_passphrase_is_rejected() {
+    let encrypted = vault().encrypt("correct horse").unwrap();
+
+    assert!(matches!(
+        Vault::decrypt(&encrypted, "battery staple"),
+        Err(Error::Decrypt)
+    ));
+}
+
+#[test]
+fn passwords_are_not_stored_in_the_clear() {
+    let encrypted = vault().encrypt("correct horse").unwrap();
+
+    assert!(!encrypted.windows(7).any(|w| w == b"hunter2"));
+    assert!(!format!("{:?}", vault()).contains("hunter2"));
+}
+
+#[test]
+fn save_replaces_the_vault() {
+    let dir = std::env::temp_dir().join(format!("rogue-vault-{}", std::process::id()));
+    let path = dir.join("vault");
+
+    vault().save(&path, "correct horse").unwrap();
+    let mut replaced = vault();
+    replaced.remove("account");
+    replaced.save(&path, "correct horse").unwrap();
+
+    assert!(
+        Vault::open(&path, "correct horse")
+            .unwrap()
+            .accounts
+            .is_empty()
+    );
+    assert_eq!(std::fs::read_dir(&dir).unwrap().count(), 1);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        let mode = std::fs::metadata(&path).unwrap().permissions().mode();
+        assert_eq!(mode & 0o777, 0o600);
+    }
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn save_ignores_stale_temp_files() {
+    let dir = std::env::temp_dir().join(format!("rogue-vault-stale-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let path = dir.join("vault");
+    // what an earlier run with the same pid would have left behind
+    std::fs::write(
+        dir.join(format!("vault.{}.tmp", std::process::id())),
+        b"junk",
+    )
+    .unwrap();
+
+    vault().save(&path, "correct horse").unwrap();
+    vault().save(&path, "correct horse").unwrap();
+
+    assert!(Vault::open(&path, "correct horse").is_ok());
+    std::fs::remove_dir_all(&dir).unwrap();
+}